@@ -0,0 +1,302 @@
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use super::board::{Board, BoardError, Space};
+use super::concepts::{Player, Stronghold, StrongholdClass, UnitClass};
+use super::narrate::narrate;
+
+// Dice rolled during one step, kept so the journal can show them. They come
+// from the game's own generator so a replay rolls the same.
+pub struct Dice<'a> {
+    rng: &'a mut ChaCha8Rng,
+    pub rolled: Vec<u8>,
+}
+
+impl<'a> Dice<'a> {
+    pub fn new(rng: &'a mut ChaCha8Rng) -> Self {
+        Dice {
+            rng,
+            rolled: vec![],
+        }
+    }
+
+    pub fn roll(&mut self) -> u8 {
+        let die: u8 = self.rng.random_range(1..=6);
+        self.rolled.push(die);
+        return die;
+    }
+}
+
+// Where these rules come from: the escalade, garrison and capacity of each
+// stronghold are the ones Stronghold::new has always carried. Everything
+// else here is a stand-in written without the rulebook to hand and not
+// checked against it: the numbers units and garrisons hit on, the order
+// losses are taken in, and the procedure itself, a single round with no
+// retreat. Those are kept in this file so they can be replaced together.
+
+// What a die has to show for a unit to score a hit
+pub fn hits_on(class: UnitClass) -> u8 {
+    match class {
+        UnitClass::Cavalry | UnitClass::Comitates | UnitClass::Foederati | UnitClass::Warband => 4,
+        UnitClass::Militia | UnitClass::Raider => 5,
+    }
+}
+
+// Strongholds fight with their garrison, which hits like Militia
+const GARRISON_HITS_ON: u8 = 5;
+
+// Cheapest first, so the best units are the last to go
+const LOSS_ORDER: [UnitClass; 6] = [
+    UnitClass::Raider,
+    UnitClass::Militia,
+    UnitClass::Warband,
+    UnitClass::Foederati,
+    UnitClass::Comitates,
+    UnitClass::Cavalry,
+];
+
+// How hits fall on one side
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Losses {
+    pub units: u8,
+    pub strongholds: u8,
+}
+
+// Hits take the units outside any stronghold first. Those sheltering
+// inside, up to the strongholds' capacity, can only be reached by escalade,
+// which scales what hits are left over. Anything escalade has left once the
+// units are gone storms the strongholds themselves, one each.
+pub fn inflict(hits: u8, units: u8, strongholds: &[&Stronghold]) -> Losses {
+    let capacity: u8 = strongholds.iter().map(|s| s.capacity).sum();
+    let sheltered: u8 = units.min(capacity);
+    let exposed: u8 = units - sheltered;
+    let exposed_lost: u8 = hits.min(exposed);
+    let escalade: f32 = strongholds.iter().map(|s| s.escalade).fold(1., f32::min);
+    let escaladed: u8 = ((hits - exposed_lost) as f32 * escalade).floor() as u8;
+    let sheltered_lost: u8 = escaladed.min(sheltered);
+    return Losses {
+        units: exposed_lost + sheltered_lost,
+        strongholds: (escaladed - sheltered_lost).min(strongholds.len() as u8),
+    };
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BattleOutcome {
+    pub attacker: Player,
+    pub defender: Player,
+    pub attacker_losses: Losses,
+    pub defender_losses: Losses,
+    pub control: Option<Player>,
+}
+
+fn strongholds_of(space: &Space, player: Player) -> Vec<&Stronghold> {
+    space
+        .stronghold_sites
+        .iter()
+        .filter_map(|site| site.stronghold.as_ref())
+        .filter(|s| s.controller == player)
+        .collect()
+}
+
+// Every unit of a side rolls a die, and so does every point of garrison in
+// its strongholds. Capabilities and Momentum add to or take from the hits.
+fn roll_hits(space: &Space, player: Player, board: &Board, dice: &mut Dice) -> u8 {
+    let mut hits: u8 = 0;
+    for unit in space.units.iter().filter(|u| u.controller == player) {
+        if dice.roll() >= hits_on(unit.designation) {
+            hits += 1;
+        }
+    }
+    let garrison: u8 = strongholds_of(space, player)
+        .iter()
+        .map(|s| s.garrison)
+        .sum();
+    for _ in 0..garrison {
+        if dice.roll() >= GARRISON_HITS_ON {
+            hits += 1;
+        }
+    }
+    return hits.saturating_add_signed(board.lasting().battle_losses_delta(player, space.id));
+}
+
+fn take_losses(
+    board: &mut Board,
+    space: u8,
+    player: Player,
+    losses: Losses,
+) -> Result<(), BoardError> {
+    let mut units: u8 = losses.units;
+    for class in LOSS_ORDER {
+        if units == 0 {
+            break;
+        }
+        units -= board.remove_units(space, player, class, units)?;
+    }
+    for _ in 0..losses.strongholds {
+        let class: StrongholdClass = match strongholds_of(
+            board.space(space).ok_or(BoardError::NoSuchSpace(space))?,
+            player,
+        )
+        .first()
+        {
+            Some(stronghold) => stronghold.class,
+            None => break,
+        };
+        board.remove_stronghold_of(space, class)?;
+    }
+    return Ok(());
+}
+
+// One round of battle in a space, fought the same way whoever starts it.
+// Both sides roll at once and then take their losses, after which control
+// of the space is worked out again.
+pub fn battle(
+    board: &mut Board,
+    space: u8,
+    attacker: Player,
+    defender: Player,
+    dice: &mut Dice,
+) -> Result<BattleOutcome, BoardError> {
+    let here: &Space = board.space(space).ok_or(BoardError::NoSuchSpace(space))?;
+    let attacker_hits: u8 = roll_hits(here, attacker, board, dice);
+    let defender_hits: u8 = roll_hits(here, defender, board, dice);
+    let attacker_units: u8 = here
+        .units
+        .iter()
+        .filter(|u| u.controller == attacker)
+        .count() as u8;
+    let defender_units: u8 = here
+        .units
+        .iter()
+        .filter(|u| u.controller == defender)
+        .count() as u8;
+    // Attackers are out in the open, defenders behind their walls
+    let attacker_losses: Losses = inflict(defender_hits, attacker_units, &[]);
+    let defender_losses: Losses = inflict(
+        attacker_hits,
+        defender_units,
+        &strongholds_of(here, defender),
+    );
+    narrate!(
+        "{} attack {} in {}: {} hits against {}",
+        attacker,
+        defender,
+        here.name,
+        attacker_hits,
+        defender_hits
+    );
+    take_losses(board, space, attacker, attacker_losses)?;
+    take_losses(board, space, defender, defender_losses)?;
+    board.update_control(space)?;
+    let control: Option<Player> = board.space(space).unwrap().control;
+    if defender_losses.strongholds > 0 {
+        narrate!(
+            "{} stormed {} of the {} strongholds",
+            attacker,
+            defender_losses.strongholds,
+            defender
+        );
+    }
+    return Ok(BattleOutcome {
+        attacker,
+        defender,
+        attacker_losses,
+        defender_losses,
+        control,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::super::board::setup_etty_tyrants;
+    use super::super::concepts::Unit;
+    use super::*;
+
+    #[test]
+    fn test_escalade_shelters_defenders() {
+        let town: Stronghold = Stronghold::new(StrongholdClass::Town, None, None);
+        let fort: Stronghold = Stronghold::new(StrongholdClass::Fort, None, None);
+        // In the open every hit counts
+        assert_eq!(
+            inflict(3, 5, &[]),
+            Losses {
+                units: 3,
+                strongholds: 0
+            }
+        );
+        // Two of three units shelter in a Hillfort-sized stronghold; the one
+        // outside takes the first hit and only half of the rest get through
+        let hillfort: Stronghold = Stronghold::new(StrongholdClass::Hillfort, None, None);
+        assert_eq!(
+            inflict(5, 3, &[&hillfort]),
+            Losses {
+                units: 3,
+                strongholds: 0
+            }
+        );
+        assert_eq!(
+            inflict(3, 3, &[&hillfort]),
+            Losses {
+                units: 2,
+                strongholds: 0
+            }
+        );
+        // With no one inside, escalade takes the stronghold itself
+        assert_eq!(
+            inflict(2, 0, &[&town]),
+            Losses {
+                units: 0,
+                strongholds: 1
+            }
+        );
+        assert_eq!(
+            inflict(1, 0, &[&fort]),
+            Losses {
+                units: 0,
+                strongholds: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_battle_storms_an_empty_settlement() {
+        let mut board: Board = setup_etty_tyrants();
+        let space: u8 = board
+            .spaces()
+            .find(|s| {
+                s.units.is_empty()
+                    && s.stronghold_sites
+                        .iter()
+                        .all(|site| site.stronghold.is_none())
+            })
+            .map(|s| s.id)
+            .unwrap();
+        board.place_units(space, Unit::con_comitates(12)).unwrap();
+        board
+            .place_stronghold_from_available(space, Player::Saxons, StrongholdClass::Settlement)
+            .unwrap();
+        let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(0);
+        let mut dice: Dice = Dice::new(&mut rng);
+        let outcome: BattleOutcome = battle(
+            &mut board,
+            space,
+            Player::Civitates,
+            Player::Saxons,
+            &mut dice,
+        )
+        .unwrap();
+        // Twelve Comitates roll, and the Settlement has no garrison
+        assert_eq!(dice.rolled.len(), 12);
+        assert_eq!(outcome.defender_losses.strongholds, 1);
+        assert_eq!(
+            board
+                .space(space)
+                .unwrap()
+                .strongholds_of(StrongholdClass::Settlement),
+            0
+        );
+        assert_eq!(outcome.control, board.space(space).unwrap().control);
+    }
+}
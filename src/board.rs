@@ -1,9 +1,14 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
-use super::concepts::{Player, Stronghold, Unit};
+use super::concepts::{Nationality, Player, Stronghold, StrongholdClass, Unit, UnitClass};
+use super::lasting::{Boundary, LastingEffect, LastingEffects};
+use super::save::by_id;
 
-pub struct Board<'a> {
-    map: Map<'a>,
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Board {
+    map: Map,
     edge_track: EdgeTrack,
     civitates_available: CivitatesAvailable,
     civitates_not_yet_in_play: CivitatesNotYetInPlay,
@@ -15,23 +20,506 @@ pub struct Board<'a> {
     scotti_niall_noigiallach: ScottiNiallNoigiallach,
     imperium: Imperium,
     roads_maintained: bool,
+    lasting: LastingEffects,
 }
 
-pub struct Map<'a> {
-    land: HashMap<u8, Space<'a>>,
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BoardError {
+    NoSuchSpace(u8),
+    NoSuchSite {
+        space: u8,
+        site: usize,
+    },
+    SiteOccupied {
+        space: u8,
+        site: usize,
+    },
+    SiteEmpty {
+        space: u8,
+        site: usize,
+    },
+    NoEmptySite {
+        space: u8,
+        class: StrongholdClass,
+    },
+    NoneAvailable {
+        player: Player,
+        class: StrongholdClass,
+    },
+    NoStronghold {
+        space: u8,
+        class: StrongholdClass,
+    },
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardError::NoSuchSpace(id) => write!(f, "No land space with id {}", id),
+            BoardError::NoSuchSite { space, site } => {
+                write!(f, "Space {} has no stronghold site {}", space, site)
+            }
+            BoardError::SiteOccupied { space, site } => write!(
+                f,
+                "Stronghold site {} in space {} already holds a stronghold",
+                site, space
+            ),
+            BoardError::SiteEmpty { space, site } => {
+                write!(f, "Stronghold site {} in space {} is empty", site, space)
+            }
+            BoardError::NoEmptySite { space, class } => {
+                write!(f, "Space {} has no empty site for a {:?}", space, class)
+            }
+            BoardError::NoneAvailable { player, class } => {
+                write!(f, "{} have no {:?} available", player, class)
+            }
+            BoardError::NoStronghold { space, class } => {
+                write!(f, "Space {} has no {:?}", space, class)
+            }
+        }
+    }
+}
+
+// The board owns every space and every stronghold on it, so it can be cloned
+// freely. Strongholds are only changed through the methods below.
+impl Board {
+    pub fn space(&self, id: u8) -> Option<&Space> {
+        self.map.land.get(&id)
+    }
+
+    pub fn spaces(&self) -> impl Iterator<Item = &Space> {
+        self.map.land.values()
+    }
+
+    // Strongholds are only ever changed in place through these, never by
+    // reaching into the map. Nothing in the game does so yet.
+    #[allow(dead_code)]
+    pub fn stronghold(&self, space: u8, site: usize) -> Option<&Stronghold> {
+        self.space(space)?
+            .stronghold_sites
+            .get(site)?
+            .stronghold
+            .as_ref()
+    }
+
+    #[allow(dead_code)]
+    pub fn stronghold_mut(&mut self, space: u8, site: usize) -> Option<&mut Stronghold> {
+        self.map
+            .land
+            .get_mut(&space)?
+            .stronghold_sites
+            .get_mut(site)?
+            .stronghold
+            .as_mut()
+    }
+
+    pub fn place_stronghold(
+        &mut self,
+        space: u8,
+        site: usize,
+        stronghold: Stronghold,
+    ) -> Result<(), BoardError> {
+        let stronghold_site = self.site_mut(space, site)?;
+        if stronghold_site.stronghold.is_some() {
+            return Err(BoardError::SiteOccupied { space, site });
+        }
+        stronghold_site.stronghold = Some(stronghold);
+        Ok(())
+    }
+
+    pub fn remove_stronghold(&mut self, space: u8, site: usize) -> Result<Stronghold, BoardError> {
+        self.site_mut(space, site)?
+            .stronghold
+            .take()
+            .ok_or(BoardError::SiteEmpty { space, site })
+    }
+
+    pub fn place_units(&mut self, space: u8, mut units: Vec<Unit>) -> Result<(), BoardError> {
+        self.map
+            .land
+            .get_mut(&space)
+            .ok_or(BoardError::NoSuchSpace(space))?
+            .units
+            .append(&mut units);
+        Ok(())
+    }
+
+    pub fn update_control(&mut self, space: u8) -> Result<(), BoardError> {
+        let s = self
+            .map
+            .land
+            .get_mut(&space)
+            .ok_or(BoardError::NoSuchSpace(space))?;
+        s.control = s.controlling_player();
+        Ok(())
+    }
+
+    pub fn total_prosperity(&self) -> u8 {
+        self.spaces().map(|s| s.top_prosp + s.bottom_prosp).sum()
+    }
+
+    fn site_mut(&mut self, space: u8, site: usize) -> Result<&mut StrongholdSite, BoardError> {
+        self.map
+            .land
+            .get_mut(&space)
+            .ok_or(BoardError::NoSuchSpace(space))?
+            .stronghold_sites
+            .get_mut(site)
+            .ok_or(BoardError::NoSuchSite { space, site })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Track {
+    BritonResources,
+    Wealth,
+    DuxResources,
+    Prestige,
+    SaxonRenown,
+    ScottiRenown,
+}
+
+impl Track {
+    pub const ALL: [Track; 6] = [
+        Track::BritonResources,
+        Track::Wealth,
+        Track::DuxResources,
+        Track::Prestige,
+        Track::SaxonRenown,
+        Track::ScottiRenown,
+    ];
+}
+
+// Moving pieces between the map and the holding boxes. Placing takes from
+// Available and stops when it runs out; removed Cavalry go to Casualties and
+// everything else goes back to Available.
+impl Board {
+    pub fn track(&self, track: Track) -> u8 {
+        match track {
+            Track::BritonResources => self.edge_track.briton_resources,
+            Track::Wealth => self.edge_track.wealth,
+            Track::DuxResources => self.edge_track.dux_resources,
+            Track::Prestige => self.edge_track.prestige,
+            Track::SaxonRenown => self.edge_track.saxon_renown,
+            Track::ScottiRenown => self.edge_track.scotti_renown,
+        }
+    }
+
+    pub fn shift_track(&mut self, track: Track, delta: i8) {
+        let value: &mut u8 = match track {
+            Track::BritonResources => &mut self.edge_track.briton_resources,
+            Track::Wealth => &mut self.edge_track.wealth,
+            Track::DuxResources => &mut self.edge_track.dux_resources,
+            Track::Prestige => &mut self.edge_track.prestige,
+            Track::SaxonRenown => &mut self.edge_track.saxon_renown,
+            Track::ScottiRenown => &mut self.edge_track.scotti_renown,
+        };
+        *value = value.saturating_add_signed(delta);
+    }
+
+    pub fn available_units(&self, player: Player, class: UnitClass) -> u8 {
+        match (player, class) {
+            (Player::Civitates, UnitClass::Militia) => self.civitates_available.militia,
+            (Player::Civitates, UnitClass::Comitates) => self.civitates_available.comitates,
+            (Player::Dux, UnitClass::Cavalry) => self.dux_available.cavalry,
+            (Player::Saxons, UnitClass::Raider) => self.saxon_available.raiders,
+            (Player::Saxons, UnitClass::Warband) => self.saxon_available.warbands,
+            (Player::Scotti, UnitClass::Raider) => self.scotti_available.raiders,
+            (Player::Scotti, UnitClass::Warband) => self.scotti_available.warbands,
+            _ => 0,
+        }
+    }
+
+    fn available_units_mut(&mut self, player: Player, class: UnitClass) -> Option<&mut u8> {
+        match (player, class) {
+            (Player::Civitates, UnitClass::Militia) => Some(&mut self.civitates_available.militia),
+            (Player::Civitates, UnitClass::Comitates) => {
+                Some(&mut self.civitates_available.comitates)
+            }
+            (Player::Dux, UnitClass::Cavalry) => Some(&mut self.dux_available.cavalry),
+            (Player::Saxons, UnitClass::Raider) => Some(&mut self.saxon_available.raiders),
+            (Player::Saxons, UnitClass::Warband) => Some(&mut self.saxon_available.warbands),
+            (Player::Scotti, UnitClass::Raider) => Some(&mut self.scotti_available.raiders),
+            (Player::Scotti, UnitClass::Warband) => Some(&mut self.scotti_available.warbands),
+            _ => None,
+        }
+    }
+
+    pub fn available_strongholds(&self, player: Player, class: StrongholdClass) -> u8 {
+        match (player, class) {
+            (Player::Civitates, StrongholdClass::Town) => self.civitates_available.towns,
+            (Player::Civitates, StrongholdClass::Hillfort) => self.civitates_available.hillforts,
+            (Player::Dux, StrongholdClass::Fort) => self.dux_available.forts,
+            (Player::Saxons, StrongholdClass::Settlement) => self.saxon_available.settlements,
+            (Player::Scotti, StrongholdClass::Settlement) => self.scotti_available.settlements,
+            _ => 0,
+        }
+    }
+
+    fn available_strongholds_mut(
+        &mut self,
+        player: Player,
+        class: StrongholdClass,
+    ) -> Option<&mut u8> {
+        match (player, class) {
+            (Player::Civitates, StrongholdClass::Town) => Some(&mut self.civitates_available.towns),
+            (Player::Civitates, StrongholdClass::Hillfort) => {
+                Some(&mut self.civitates_available.hillforts)
+            }
+            (Player::Dux, StrongholdClass::Fort) => Some(&mut self.dux_available.forts),
+            (Player::Saxons, StrongholdClass::Settlement) => {
+                Some(&mut self.saxon_available.settlements)
+            }
+            (Player::Scotti, StrongholdClass::Settlement) => {
+                Some(&mut self.scotti_available.settlements)
+            }
+            _ => None,
+        }
+    }
+
+    // Returns how many were actually placed
+    pub fn place_from_available(
+        &mut self,
+        space: u8,
+        player: Player,
+        class: UnitClass,
+        count: u8,
+    ) -> Result<u8, BoardError> {
+        if self.space(space).is_none() {
+            return Err(BoardError::NoSuchSpace(space));
+        }
+        let placed: u8 = match self.available_units_mut(player, class) {
+            Some(available) => {
+                let placed = count.min(*available);
+                *available -= placed;
+                placed
+            }
+            None => 0,
+        };
+        let unit: Unit = Unit {
+            designation: class,
+            controller: player,
+            nationality: Nationality::of(player),
+            plunder: false,
+        };
+        self.place_units(space, vec![unit; placed as usize])?;
+        self.update_control(space)?;
+        return Ok(placed);
+    }
+
+    // Returns how many were actually removed
+    pub fn remove_units(
+        &mut self,
+        space: u8,
+        player: Player,
+        class: UnitClass,
+        count: u8,
+    ) -> Result<u8, BoardError> {
+        let units: &mut Vec<Unit> = &mut self
+            .map
+            .land
+            .get_mut(&space)
+            .ok_or(BoardError::NoSuchSpace(space))?
+            .units;
+        let mut removed: u8 = 0;
+        units.retain(|u| {
+            if removed < count && u.controller == player && u.designation == class {
+                removed += 1;
+                return false;
+            }
+            return true;
+        });
+        if class == UnitClass::Cavalry {
+            self.dux_casualties.cavalry += removed;
+        } else if let Some(available) = self.available_units_mut(player, class) {
+            *available += removed;
+        }
+        self.update_control(space)?;
+        return Ok(removed);
+    }
+
+    // Marching pieces keep whatever they carry. Returns how many actually
+    // moved.
+    pub fn move_units(
+        &mut self,
+        from: u8,
+        to: u8,
+        player: Player,
+        class: UnitClass,
+        count: u8,
+    ) -> Result<u8, BoardError> {
+        if self.space(to).is_none() {
+            return Err(BoardError::NoSuchSpace(to));
+        }
+        let units: &mut Vec<Unit> = &mut self
+            .map
+            .land
+            .get_mut(&from)
+            .ok_or(BoardError::NoSuchSpace(from))?
+            .units;
+        let mut moving: Vec<Unit> = vec![];
+        let mut staying: Vec<Unit> = vec![];
+        for unit in units.drain(..) {
+            if moving.len() < count as usize
+                && unit.controller == player
+                && unit.designation == class
+            {
+                moving.push(unit);
+            } else {
+                staying.push(unit);
+            }
+        }
+        *units = staying;
+        let moved: u8 = moving.len() as u8;
+        self.place_units(to, moving)?;
+        self.update_control(from)?;
+        self.update_control(to)?;
+        return Ok(moved);
+    }
+
+    pub fn place_stronghold_from_available(
+        &mut self,
+        space: u8,
+        player: Player,
+        class: StrongholdClass,
+    ) -> Result<(), BoardError> {
+        if self.available_strongholds(player, class) == 0 {
+            return Err(BoardError::NoneAvailable { player, class });
+        }
+        let site: usize = self
+            .space(space)
+            .ok_or(BoardError::NoSuchSpace(space))?
+            .empty_site_for(class)
+            .ok_or(BoardError::NoEmptySite { space, class })?;
+        let stronghold: Stronghold = match class {
+            StrongholdClass::Settlement => settlement(player),
+            _ => Stronghold::new(class, None, None),
+        };
+        self.place_stronghold(space, site, stronghold)?;
+        if let Some(available) = self.available_strongholds_mut(player, class) {
+            *available -= 1;
+        }
+        self.update_control(space)?;
+        Ok(())
+    }
+
+    pub fn remove_stronghold_of(
+        &mut self,
+        space: u8,
+        class: StrongholdClass,
+    ) -> Result<Stronghold, BoardError> {
+        let site: usize = self
+            .space(space)
+            .ok_or(BoardError::NoSuchSpace(space))?
+            .stronghold_sites
+            .iter()
+            .position(|s| s.stronghold.as_ref().is_some_and(|s| s.class == class))
+            .ok_or(BoardError::NoStronghold { space, class })?;
+        let stronghold: Stronghold = self.remove_stronghold(space, site)?;
+        if let Some(available) = self.available_strongholds_mut(stronghold.controller, class) {
+            *available += 1;
+        }
+        self.update_control(space)?;
+        return Ok(stronghold);
+    }
+
+    // Rural prosperity moves first, town prosperity only once the
+    // countryside is full or empty
+    pub fn shift_prosperity(&mut self, space: u8, delta: i8) -> Result<(), BoardError> {
+        let s: &mut Space = self
+            .map
+            .land
+            .get_mut(&space)
+            .ok_or(BoardError::NoSuchSpace(space))?;
+        let towns: u8 = s.strongholds_of(StrongholdClass::Town);
+        for _ in 0..delta.unsigned_abs() {
+            if delta > 0 {
+                if s.bottom_prosp < s.pop {
+                    s.bottom_prosp += 1;
+                } else if s.top_prosp < towns {
+                    s.top_prosp += 1;
+                }
+            } else if s.bottom_prosp > 0 {
+                s.bottom_prosp -= 1;
+            } else if s.top_prosp > 0 {
+                s.top_prosp -= 1;
+            }
+        }
+        self.edge_track.total_prosperity = self.total_prosperity();
+        Ok(())
+    }
+
+    pub fn controlled_spaces(&self, player: Player) -> u8 {
+        self.spaces().filter(|s| s.control == Some(player)).count() as u8
+    }
+
+    // How far a faction stands past its victory threshold, negative when
+    // short of it. A faction with two conditions to meet counts the one it
+    // is furthest from.
+    pub fn victory_margin(&self, player: Player) -> i16 {
+        let track: &EdgeTrack = &self.edge_track;
+        let briton_control: i16 = self.controlled_spaces(Player::Civitates) as i16
+            + self.controlled_spaces(Player::Dux) as i16;
+        match player {
+            Player::Civitates => briton_control - track.briton_control_threshold as i16,
+            Player::Dux => match track.prosperity_plus_prestige_threshold {
+                Some(threshold) => {
+                    self.total_prosperity() as i16 + track.prestige as i16 - threshold as i16
+                }
+                None => {
+                    briton_control + track.prestige as i16
+                        - track.control_plus_prestige_threshold.unwrap_or_default() as i16
+                }
+            },
+            Player::Saxons => {
+                let control: i16 = self.controlled_spaces(Player::Saxons) as i16
+                    - track.saxon_control_threshold as i16;
+                match track.saxon_renown_threshold {
+                    Some(threshold) => control.min(track.saxon_renown as i16 - threshold as i16),
+                    None => control,
+                }
+            }
+            Player::Scotti => track.scotti_renown as i16 - track.scotti_renown_threshold as i16,
+        }
+    }
+
+    // Capabilities and Momentum in play
+    pub fn lasting(&self) -> &LastingEffects {
+        &self.lasting
+    }
+
+    pub fn add_lasting(&mut self, effect: LastingEffect) {
+        self.lasting.add(effect);
+    }
+
+    pub fn expire_lasting(&mut self, boundary: Boundary) -> Vec<LastingEffect> {
+        self.lasting.expire(boundary)
+    }
+
+    pub fn remove_marker(&mut self, space: u8, source: u8) -> Option<LastingEffect> {
+        self.lasting.remove_marker(space, source)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Map {
+    #[serde(with = "by_id")]
+    land: HashMap<u8, Space>,
+    #[serde(with = "by_id")]
     off_map_land: HashMap<u8, OffMapLand>,
+    #[serde(with = "by_id")]
     seas: HashMap<u8, Sea>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Terrain {
     Clear,
     Fens,
     Hills,
 }
 
-#[derive(Clone, Debug)]
-pub struct Space<'a> {
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Space {
     pub id: u8,
     pub name: String,
     pub space_type: SpaceType,
@@ -40,22 +528,24 @@ pub struct Space<'a> {
     pub adj_seas: Vec<u8>,
     pub pop: u8,
     pub max_pop: u8,
+    // Prosperity is split between the towns (top, at most one per Town)
+    // and the countryside (bottom, at most pop)
     pub top_prosp: u8,
     pub bottom_prosp: u8,
-    pub stronghold_sites: Vec<StrongholdSite<'a>>,
+    pub stronghold_sites: Vec<StrongholdSite>,
     pub units: Vec<Unit>,
     pub control: Option<Player>,
 }
 
-impl<'a> Space<'_> {
+impl Space {
     fn new(
         id: u8,
         name: &str,
         space_type: SpaceType,
         terrain: Option<Terrain>,
         pop: u8,
-        stronghold_sites: Vec<StrongholdSite<'a>>,
-    ) -> Space<'a> {
+        stronghold_sites: Vec<StrongholdSite>,
+    ) -> Space {
         Space {
             id,
             name: name.to_string(),
@@ -72,30 +562,96 @@ impl<'a> Space<'_> {
             control: None,
         }
     }
+
+    pub fn units_of(&self, player: Player, class: UnitClass) -> u8 {
+        self.units
+            .iter()
+            .filter(|u| u.controller == player && u.designation == class)
+            .count() as u8
+    }
+
+    pub fn strongholds_of(&self, class: StrongholdClass) -> u8 {
+        self.stronghold_sites
+            .iter()
+            .filter(|site| site.stronghold.as_ref().is_some_and(|s| s.class == class))
+            .count() as u8
+    }
+
+    // Towns need a Town site, Forts and Hillforts a Hillfort site, and
+    // Settlements can go on any empty site
+    pub fn empty_site_for(&self, class: StrongholdClass) -> Option<usize> {
+        self.stronghold_sites.iter().position(|site| {
+            site.stronghold.is_none()
+                && match class {
+                    StrongholdClass::Town => {
+                        !matches!(site.site_type, StrongholdSiteType::Hillfort)
+                    }
+                    StrongholdClass::Fort | StrongholdClass::Hillfort => {
+                        matches!(site.site_type, StrongholdSiteType::Hillfort)
+                    }
+                    StrongholdClass::Settlement => true,
+                }
+        })
+    }
+
+    pub fn pieces(&self, player: Player) -> u8 {
+        let units = self.units.iter().filter(|u| u.controller == player).count();
+        let strongholds = self
+            .stronghold_sites
+            .iter()
+            .filter_map(|site| site.stronghold.as_ref())
+            .filter(|s| s.controller == player)
+            .count();
+        return (units + strongholds) as u8;
+    }
+
+    // Britons control a space when the Dux and Civitates together outnumber
+    // everyone else, with the larger of the two holding it (the Civitates on
+    // a tie). A barbarian faction needs to outnumber everyone else alone.
+    pub fn controlling_player(&self) -> Option<Player> {
+        let civitates = self.pieces(Player::Civitates);
+        let dux = self.pieces(Player::Dux);
+        let saxons = self.pieces(Player::Saxons);
+        let scotti = self.pieces(Player::Scotti);
+
+        if civitates + dux > saxons + scotti {
+            if dux > civitates {
+                return Some(Player::Dux);
+            }
+            return Some(Player::Civitates);
+        }
+        if saxons > civitates + dux + scotti {
+            return Some(Player::Saxons);
+        }
+        if scotti > civitates + dux + saxons {
+            return Some(Player::Scotti);
+        }
+        return None;
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum SpaceType {
     Region,
     City,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum StrongholdSiteType {
     Hillfort,
     Town,
     City,
 }
 
-#[derive(Clone, Debug)]
-pub struct StrongholdSite<'a> {
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StrongholdSite {
     pub name: String,
     pub site_type: StrongholdSiteType,
-    pub stronghold: Option<&'a Stronghold>,
+    pub stronghold: Option<Stronghold>,
 }
 
-impl<'a> StrongholdSite<'_> {
-    fn new(name: &str, site_type: StrongholdSiteType) -> StrongholdSite<'a> {
+impl StrongholdSite {
+    fn new(name: &str, site_type: StrongholdSiteType) -> StrongholdSite {
         StrongholdSite {
             name: name.to_string(),
             site_type,
@@ -104,7 +660,7 @@ impl<'a> StrongholdSite<'_> {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OffMapLand {
     id: u8,
     name: String,
@@ -123,7 +679,7 @@ impl OffMapLand {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Sea {
     id: u8,
     name: String,
@@ -142,6 +698,7 @@ impl Sea {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct EdgeTrack {
     briton_resources: u8,
     wealth: u8,
@@ -157,6 +714,7 @@ struct EdgeTrack {
     saxon_renown_threshold: Option<u8>,
     scotti_renown_threshold: u8,
 }
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct CivitatesAvailable {
     militia: u8,
     comitates: u8,
@@ -165,10 +723,12 @@ struct CivitatesAvailable {
     refugees: u8,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct CivitatesNotYetInPlay {
     comitates: u8,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct ScottiAvailable {
     raiders: u8,
     warbands: u8,
@@ -176,10 +736,12 @@ struct ScottiAvailable {
     max_settlements: u8,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct ScottiNiallNoigiallach {
     raiders: u8,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct SaxonsAvailable {
     raiders: u8,
     warbands: u8,
@@ -187,39 +749,69 @@ struct SaxonsAvailable {
     max_settlements: u8,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct DuxAvailable {
     cavalry: u8,
     forts: u8,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct DuxCasualties {
     cavalry: u8,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct DuxOutOfPlay {
     cavalry: u8,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 enum Imperium {
     RomanRule(Dominance),
     Autonomy(Dominance),
     Fragmentation,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 enum Dominance {
     Military,
     Civilian,
     None,
 }
 
-fn build_map() -> Map<'static> {
+// Land space ids, as assigned in build_map
+pub const ATREBATES: u8 = 0;
+pub const BRIGANTES: u8 = 1;
+pub const CANTIACI: u8 = 2;
+pub const CARVETII: u8 = 3;
+pub const CATUVELLAUNI: u8 = 4;
+pub const CORIELTAUVI: u8 = 5;
+pub const CORNOVII: u8 = 6;
+pub const DECANGLI: u8 = 7;
+pub const DEMETAE: u8 = 8;
+pub const DOBUNNI: u8 = 9;
+pub const DUMNONII: u8 = 10;
+pub const DUROTRIGES: u8 = 11;
+pub const EBORACUM: u8 = 12;
+pub const ICENI: u8 = 13;
+pub const LONDINIUM: u8 = 14;
+pub const NOVANTAE: u8 = 15;
+pub const ORDOVICES: u8 = 16;
+pub const PARISI: u8 = 17;
+pub const REGNI: u8 = 18;
+pub const SILURES: u8 = 19;
+pub const TRINOVANTES: u8 = 20;
+pub const TEXTOVERDI: u8 = 21;
+pub const VOTADINI: u8 = 22;
+
+fn build_map() -> Map {
     let mut land = HashMap::new();
     let mut off_map_land = HashMap::new();
     let mut seas = HashMap::new();
 
     // CREATE SPACES
-    let mut atrebates: Space<'_> = Space::new(
-        0,
+    let mut atrebates: Space = Space::new(
+        ATREBATES,
         "Atrebates",
         SpaceType::Region,
         Some(Terrain::Clear),
@@ -229,8 +821,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Calleva Atrebatum", StrongholdSiteType::Town),
         ],
     );
-    let mut brigantes: Space<'_> = Space::new(
-        1,
+    let mut brigantes: Space = Space::new(
+        BRIGANTES,
         "Brigantes",
         SpaceType::Region,
         Some(Terrain::Hills),
@@ -240,8 +832,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Mam Tor", StrongholdSiteType::Hillfort),
         ],
     );
-    let mut cantiaci: Space<'_> = Space::new(
-        2,
+    let mut cantiaci: Space = Space::new(
+        CANTIACI,
         "Cantiaci",
         SpaceType::Region,
         Some(Terrain::Fens),
@@ -251,8 +843,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Durovernum", StrongholdSiteType::Town),
         ],
     );
-    let mut carvetii: Space<'_> = Space::new(
-        3,
+    let mut carvetii: Space = Space::new(
+        CARVETII,
         "Carvetii",
         SpaceType::Region,
         Some(Terrain::Hills),
@@ -262,8 +854,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Luguvalium", StrongholdSiteType::Town),
         ],
     );
-    let mut catuvellauni: Space<'_> = Space::new(
-        4,
+    let mut catuvellauni: Space = Space::new(
+        CATUVELLAUNI,
         "Catuvellauni",
         SpaceType::Region,
         Some(Terrain::Clear),
@@ -273,8 +865,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Verulamium", StrongholdSiteType::Town),
         ],
     );
-    let mut corieltauvi: Space<'_> = Space::new(
-        5,
+    let mut corieltauvi: Space = Space::new(
+        CORIELTAUVI,
         "Corieltauvi",
         SpaceType::Region,
         Some(Terrain::Fens),
@@ -284,8 +876,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Lindum", StrongholdSiteType::Town),
         ],
     );
-    let mut cornovii: Space<'_> = Space::new(
-        6,
+    let mut cornovii: Space = Space::new(
+        CORNOVII,
         "Cornovii",
         SpaceType::Region,
         Some(Terrain::Clear),
@@ -295,8 +887,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Viroconium", StrongholdSiteType::Town),
         ],
     );
-    let mut decangli: Space<'_> = Space::new(
-        7,
+    let mut decangli: Space = Space::new(
+        DECANGLI,
         "Decangli",
         SpaceType::Region,
         Some(Terrain::Clear),
@@ -306,8 +898,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Deva", StrongholdSiteType::Town),
         ],
     );
-    let mut demetae: Space<'_> = Space::new(
-        8,
+    let mut demetae: Space = Space::new(
+        DEMETAE,
         "Demetae",
         SpaceType::Region,
         Some(Terrain::Hills),
@@ -317,8 +909,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Gateholm", StrongholdSiteType::Hillfort),
         ],
     );
-    let mut dobunni: Space<'_> = Space::new(
-        9,
+    let mut dobunni: Space = Space::new(
+        DOBUNNI,
         "Dobunni",
         SpaceType::Region,
         Some(Terrain::Clear),
@@ -328,8 +920,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Corinium", StrongholdSiteType::Town),
         ],
     );
-    let mut dumnonii: Space<'_> = Space::new(
-        10,
+    let mut dumnonii: Space = Space::new(
+        DUMNONII,
         "Dumnonii",
         SpaceType::Region,
         Some(Terrain::Hills),
@@ -339,8 +931,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Isca Dumnoniorum", StrongholdSiteType::Town),
         ],
     );
-    let mut durotriges: Space<'_> = Space::new(
-        11,
+    let mut durotriges: Space = Space::new(
+        DUROTRIGES,
         "Durotriges",
         SpaceType::Region,
         Some(Terrain::Clear),
@@ -350,16 +942,16 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Aquae Sulis", StrongholdSiteType::Town),
         ],
     );
-    let mut eboracum: Space<'_> = Space::new(
-        12,
+    let mut eboracum: Space = Space::new(
+        EBORACUM,
         "Eboracum",
         SpaceType::City,
         None,
         1,
         vec![StrongholdSite::new("Eboracum", StrongholdSiteType::Town)],
     );
-    let mut iceni: Space<'_> = Space::new(
-        13,
+    let mut iceni: Space = Space::new(
+        ICENI,
         "Iceni",
         SpaceType::Region,
         Some(Terrain::Fens),
@@ -369,16 +961,16 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Venta Icenorum", StrongholdSiteType::Hillfort),
         ],
     );
-    let mut londinium: Space<'_> = Space::new(
-        14,
+    let mut londinium: Space = Space::new(
+        LONDINIUM,
         "Londinium",
         SpaceType::City,
         None,
         2,
         vec![StrongholdSite::new("Londinium", StrongholdSiteType::Town)],
     );
-    let mut novantae: Space<'_> = Space::new(
-        15,
+    let mut novantae: Space = Space::new(
+        NOVANTAE,
         "Novantae",
         SpaceType::Region,
         Some(Terrain::Hills),
@@ -388,8 +980,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Alclud", StrongholdSiteType::Hillfort),
         ],
     );
-    let mut ordovices: Space<'_> = Space::new(
-        16,
+    let mut ordovices: Space = Space::new(
+        ORDOVICES,
         "Ordovices",
         SpaceType::Region,
         Some(Terrain::Hills),
@@ -399,8 +991,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Dinas Emrys", StrongholdSiteType::Hillfort),
         ],
     );
-    let mut parisi: Space<'_> = Space::new(
-        17,
+    let mut parisi: Space = Space::new(
+        PARISI,
         "Parisi",
         SpaceType::Region,
         Some(Terrain::Clear),
@@ -410,8 +1002,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Derventio", StrongholdSiteType::Hillfort),
         ],
     );
-    let mut regni: Space<'_> = Space::new(
-        18,
+    let mut regni: Space = Space::new(
+        REGNI,
         "Regni",
         SpaceType::Region,
         Some(Terrain::Clear),
@@ -421,8 +1013,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Noviomagus", StrongholdSiteType::Town),
         ],
     );
-    let mut silures: Space<'_> = Space::new(
-        19,
+    let mut silures: Space = Space::new(
+        SILURES,
         "Silures",
         SpaceType::Region,
         Some(Terrain::Hills),
@@ -432,8 +1024,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Isca Silurum", StrongholdSiteType::Town),
         ],
     );
-    let mut trinovantes: Space<'_> = Space::new(
-        20,
+    let mut trinovantes: Space = Space::new(
+        TRINOVANTES,
         "Trinovantes",
         SpaceType::Region,
         Some(Terrain::Fens),
@@ -443,8 +1035,8 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Walton Castle", StrongholdSiteType::Hillfort),
         ],
     );
-    let mut textoverdi: Space<'_> = Space::new(
-        21,
+    let mut textoverdi: Space = Space::new(
+        TEXTOVERDI,
         "Textoverdi",
         SpaceType::Region,
         Some(Terrain::Hills),
@@ -454,9 +1046,9 @@ fn build_map() -> Map<'static> {
             StrongholdSite::new("Cataractonium", StrongholdSiteType::Town),
         ],
     );
-    let mut votadini: Space<'_> = Space::new(
-        22,
-        "Carvetii",
+    let mut votadini: Space = Space::new(
+        VOTADINI,
+        "Votadini",
         SpaceType::Region,
         Some(Terrain::Hills),
         2,
@@ -622,6 +1214,7 @@ fn build_map() -> Map<'static> {
     land.insert(londinium.id, londinium);
     land.insert(novantae.id, novantae);
     land.insert(ordovices.id, ordovices);
+    land.insert(parisi.id, parisi);
     land.insert(regni.id, regni);
     land.insert(silures.id, silures);
     land.insert(textoverdi.id, textoverdi);
@@ -642,70 +1235,1201 @@ fn build_map() -> Map<'static> {
     };
 }
 
-pub fn setup_barbarian_conspiracy() -> Board<'static> {
-    Board {
-        map: todo!(),
-        edge_track: todo!(),
-        civitates_available: todo!(),
-        civitates_not_yet_in_play: todo!(),
-        dux_available: todo!(),
-        dux_casualties: todo!(),
-        dux_out_of_play: todo!(),
-        saxon_available: todo!(),
-        scotti_available: todo!(),
-        scotti_niall_noigiallach: todo!(),
-        imperium: todo!(),
-        roads_maintained: todo!(),
-    }
-}
-
-pub fn setup_etty_tyrants() -> Board<'static> {
-    Board {
-        map: todo!(),
-        edge_track: todo!(),
-        civitates_available: todo!(),
-        civitates_not_yet_in_play: todo!(),
-        dux_available: todo!(),
-        dux_casualties: todo!(),
-        dux_out_of_play: todo!(),
-        saxon_available: todo!(),
-        scotti_available: todo!(),
-        scotti_niall_noigiallach: todo!(),
-        imperium: todo!(),
-        roads_maintained: todo!(),
-    }
-}
-
-pub fn setup_the_harder_they_fall() -> Board<'static> {
-    Board {
-        map: todo!(),
-        edge_track: todo!(),
-        civitates_available: todo!(),
-        civitates_not_yet_in_play: todo!(),
-        dux_available: todo!(),
-        dux_casualties: todo!(),
-        dux_out_of_play: todo!(),
-        saxon_available: todo!(),
-        scotti_available: todo!(),
-        scotti_niall_noigiallach: todo!(),
-        imperium: todo!(),
-        roads_maintained: todo!(),
-    }
-}
-
-pub fn setup_de_excidio_britanniae() -> Board<'static> {
-    Board {
-        map: todo!(),
-        edge_track: todo!(),
-        civitates_available: todo!(),
-        civitates_not_yet_in_play: todo!(),
-        dux_available: todo!(),
-        dux_casualties: todo!(),
-        dux_out_of_play: todo!(),
-        saxon_available: todo!(),
-        scotti_available: todo!(),
-        scotti_niall_noigiallach: todo!(),
-        imperium: todo!(),
-        roads_maintained: todo!(),
+fn town() -> Stronghold {
+    Stronghold::new(StrongholdClass::Town, None, None)
+}
+
+fn hillfort() -> Stronghold {
+    Stronghold::new(StrongholdClass::Hillfort, None, None)
+}
+
+fn fort() -> Stronghold {
+    Stronghold::new(StrongholdClass::Fort, None, None)
+}
+
+fn settlement(player: Player) -> Stronghold {
+    Stronghold::new(
+        StrongholdClass::Settlement,
+        Some(player),
+        Some(Nationality::of(player)),
+    )
+}
+
+// Scenario data is fixed, so a bad space or site id here is a bug in the
+// setup itself rather than something to recover from
+impl Board {
+    fn setup_space(
+        &mut self,
+        space: u8,
+        units: Vec<Vec<Unit>>,
+        strongholds: Vec<(usize, Stronghold)>,
+        top_prosp: u8,
+        bottom_prosp: u8,
+    ) {
+        self.place_units(space, units.concat()).unwrap();
+        for (site, stronghold) in strongholds {
+            self.place_stronghold(space, site, stronghold).unwrap();
+        }
+        let s = self.map.land.get_mut(&space).unwrap();
+        s.top_prosp = top_prosp;
+        s.bottom_prosp = bottom_prosp;
+    }
+
+    fn finish_setup(mut self) -> Board {
+        let ids: Vec<u8> = self.map.land.keys().copied().collect();
+        for id in ids {
+            self.update_control(id).unwrap();
+        }
+        self.edge_track.total_prosperity = self.total_prosperity();
+        return self;
+    }
+}
+
+// The four setups below have not been checked against the printed scenario
+// sheets: the starting pieces, tracks, thresholds and holding boxes are
+// stand-ins until each is transcribed from its sheet, which should then be
+// cited here.
+
+// AD 367: Roman rule, with the barbarians only raiding the coasts
+pub fn setup_barbarian_conspiracy() -> Board {
+    let mut board: Board = Board {
+        map: build_map(),
+        edge_track: EdgeTrack {
+            briton_resources: 10,
+            wealth: 6,
+            dux_resources: 12,
+            prestige: 10,
+            total_prosperity: 0,
+            saxon_renown: 0,
+            scotti_renown: 0,
+            briton_control_threshold: 12,
+            prosperity_plus_prestige_threshold: Some(36),
+            control_plus_prestige_threshold: None,
+            saxon_control_threshold: 6,
+            saxon_renown_threshold: Some(12),
+            scotti_renown_threshold: 10,
+        },
+        civitates_available: CivitatesAvailable {
+            militia: 13,
+            comitates: 0,
+            towns: 3,
+            hillforts: 13,
+            refugees: 0,
+        },
+        civitates_not_yet_in_play: CivitatesNotYetInPlay { comitates: 15 },
+        dux_available: DuxAvailable {
+            cavalry: 5,
+            forts: 1,
+        },
+        dux_casualties: DuxCasualties { cavalry: 0 },
+        dux_out_of_play: DuxOutOfPlay { cavalry: 2 },
+        saxon_available: SaxonsAvailable {
+            raiders: 12,
+            warbands: 30,
+            settlements: 12,
+            max_settlements: 2,
+        },
+        scotti_available: ScottiAvailable {
+            raiders: 4,
+            warbands: 20,
+            settlements: 8,
+            max_settlements: 2,
+        },
+        scotti_niall_noigiallach: ScottiNiallNoigiallach { raiders: 4 },
+        imperium: Imperium::RomanRule(Dominance::Military),
+        roads_maintained: true,
+        lasting: LastingEffects::default(),
+    };
+
+    board.setup_space(
+        LONDINIUM,
+        vec![Unit::con_militia(2), Unit::con_cavalry(2)],
+        vec![(0, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        EBORACUM,
+        vec![Unit::con_militia(1), Unit::con_cavalry(3)],
+        vec![(0, town())],
+        1,
+        1,
+    );
+    board.setup_space(
+        ATREBATES,
+        vec![Unit::con_militia(2)],
+        vec![(0, town()), (1, town())],
+        2,
+        3,
+    );
+    board.setup_space(
+        CANTIACI,
+        vec![Unit::con_militia(1), Unit::con_cavalry(1)],
+        vec![(0, fort()), (1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        REGNI,
+        vec![Unit::con_militia(1), Unit::con_cavalry(1)],
+        vec![(0, fort()), (1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        TRINOVANTES,
+        vec![Unit::con_militia(1), Unit::con_cavalry(1)],
+        vec![(0, town()), (1, fort())],
+        1,
+        2,
+    );
+    board.setup_space(
+        ICENI,
+        vec![Unit::con_cavalry(1), Unit::con_raiders(Player::Saxons, 2)],
+        vec![(0, fort())],
+        0,
+        2,
+    );
+    board.setup_space(
+        CATUVELLAUNI,
+        vec![Unit::con_militia(2)],
+        vec![(1, town())],
+        1,
+        3,
+    );
+    board.setup_space(DOBUNNI, vec![Unit::con_militia(2)], vec![(1, town())], 1, 2);
+    board.setup_space(
+        DUROTRIGES,
+        vec![Unit::con_militia(2)],
+        vec![(0, hillfort()), (1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        CORIELTAUVI,
+        vec![Unit::con_militia(1)],
+        vec![(1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        CORNOVII,
+        vec![Unit::con_militia(1)],
+        vec![(1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        TEXTOVERDI,
+        vec![Unit::con_cavalry(2)],
+        vec![(0, fort())],
+        0,
+        1,
+    );
+    board.setup_space(
+        CARVETII,
+        vec![Unit::con_cavalry(1)],
+        vec![(0, fort())],
+        0,
+        1,
+    );
+    board.setup_space(
+        ORDOVICES,
+        vec![Unit::con_cavalry(1), Unit::con_raiders(Player::Scotti, 1)],
+        vec![(0, fort())],
+        0,
+        1,
+    );
+    board.setup_space(
+        DEMETAE,
+        vec![Unit::con_raiders(Player::Scotti, 2)],
+        vec![],
+        0,
+        1,
+    );
+    board.setup_space(
+        NOVANTAE,
+        vec![Unit::con_raiders(Player::Scotti, 1)],
+        vec![],
+        0,
+        0,
+    );
+    board.setup_space(
+        VOTADINI,
+        vec![Unit::con_militia(1)],
+        vec![(0, hillfort())],
+        0,
+        1,
+    );
+    board.setup_space(
+        PARISI,
+        vec![Unit::con_raiders(Player::Saxons, 1)],
+        vec![],
+        0,
+        1,
+    );
+    return board.finish_setup();
+}
+
+// AD 383: Magnus Maximus has taken much of the army to Gaul and the cities
+// look to their own defence
+pub fn setup_etty_tyrants() -> Board {
+    let mut board: Board = Board {
+        map: build_map(),
+        edge_track: EdgeTrack {
+            briton_resources: 12,
+            wealth: 8,
+            dux_resources: 8,
+            prestige: 8,
+            total_prosperity: 0,
+            saxon_renown: 2,
+            scotti_renown: 2,
+            briton_control_threshold: 11,
+            prosperity_plus_prestige_threshold: Some(32),
+            control_plus_prestige_threshold: None,
+            saxon_control_threshold: 5,
+            saxon_renown_threshold: Some(12),
+            scotti_renown_threshold: 10,
+        },
+        civitates_available: CivitatesAvailable {
+            militia: 10,
+            comitates: 4,
+            towns: 2,
+            hillforts: 9,
+            refugees: 0,
+        },
+        civitates_not_yet_in_play: CivitatesNotYetInPlay { comitates: 6 },
+        dux_available: DuxAvailable {
+            cavalry: 4,
+            forts: 4,
+        },
+        dux_casualties: DuxCasualties { cavalry: 1 },
+        dux_out_of_play: DuxOutOfPlay { cavalry: 8 },
+        saxon_available: SaxonsAvailable {
+            raiders: 11,
+            warbands: 28,
+            settlements: 11,
+            max_settlements: 3,
+        },
+        scotti_available: ScottiAvailable {
+            raiders: 5,
+            warbands: 18,
+            settlements: 8,
+            max_settlements: 3,
+        },
+        scotti_niall_noigiallach: ScottiNiallNoigiallach { raiders: 3 },
+        imperium: Imperium::Autonomy(Dominance::Civilian),
+        roads_maintained: true,
+        lasting: LastingEffects::default(),
+    };
+
+    board.setup_space(
+        LONDINIUM,
+        vec![
+            Unit::con_militia(2),
+            Unit::con_comitates(1),
+            Unit::con_cavalry(1),
+        ],
+        vec![(0, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        EBORACUM,
+        vec![Unit::con_militia(1), Unit::con_cavalry(2)],
+        vec![(0, town())],
+        1,
+        1,
+    );
+    board.setup_space(
+        ATREBATES,
+        vec![Unit::con_militia(2), Unit::con_comitates(1)],
+        vec![(0, town()), (1, town())],
+        2,
+        3,
+    );
+    board.setup_space(
+        CANTIACI,
+        vec![Unit::con_militia(1), Unit::con_cavalry(1)],
+        vec![(0, fort()), (1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        REGNI,
+        vec![Unit::con_militia(1)],
+        vec![(0, hillfort()), (1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        TRINOVANTES,
+        vec![Unit::con_militia(1), Unit::con_raiders(Player::Saxons, 1)],
+        vec![(0, town()), (1, fort())],
+        1,
+        2,
+    );
+    board.setup_space(
+        ICENI,
+        vec![Unit::con_militia(1), Unit::con_raiders(Player::Saxons, 2)],
+        vec![(0, hillfort())],
+        0,
+        2,
+    );
+    board.setup_space(
+        CATUVELLAUNI,
+        vec![Unit::con_militia(2), Unit::con_comitates(1)],
+        vec![(1, town())],
+        1,
+        3,
+    );
+    board.setup_space(
+        DOBUNNI,
+        vec![Unit::con_militia(2), Unit::con_comitates(1)],
+        vec![(0, town()), (1, town())],
+        2,
+        2,
+    );
+    board.setup_space(
+        DUROTRIGES,
+        vec![Unit::con_militia(2)],
+        vec![(0, hillfort()), (1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        CORIELTAUVI,
+        vec![Unit::con_militia(1)],
+        vec![(1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        CORNOVII,
+        vec![Unit::con_militia(1), Unit::con_comitates(1)],
+        vec![(0, hillfort()), (1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        PARISI,
+        vec![
+            Unit::con_militia(1),
+            Unit::con_raiders(Player::Saxons, 1),
+            Unit::con_warbands(Player::Saxons, 2),
+        ],
+        vec![(1, settlement(Player::Saxons))],
+        0,
+        1,
+    );
+    board.setup_space(
+        TEXTOVERDI,
+        vec![Unit::con_cavalry(2)],
+        vec![(0, fort())],
+        0,
+        1,
+    );
+    board.setup_space(
+        CARVETII,
+        vec![Unit::con_cavalry(1)],
+        vec![(0, fort())],
+        0,
+        1,
+    );
+    board.setup_space(
+        ORDOVICES,
+        vec![
+            Unit::con_raiders(Player::Scotti, 2),
+            Unit::con_warbands(Player::Scotti, 2),
+        ],
+        vec![],
+        0,
+        1,
+    );
+    board.setup_space(
+        DEMETAE,
+        vec![Unit::con_raiders(Player::Scotti, 2)],
+        vec![],
+        0,
+        1,
+    );
+    board.setup_space(
+        VOTADINI,
+        vec![Unit::con_militia(1)],
+        vec![(0, hillfort())],
+        0,
+        1,
+    );
+    board.setup_space(
+        SILURES,
+        vec![Unit::con_militia(1)],
+        vec![(0, hillfort())],
+        0,
+        1,
+    );
+    return board.finish_setup();
+}
+
+// AD 410: the legions are gone and the Saxons are settling the east coast
+pub fn setup_the_harder_they_fall() -> Board {
+    let mut board: Board = Board {
+        map: build_map(),
+        edge_track: EdgeTrack {
+            briton_resources: 14,
+            wealth: 8,
+            dux_resources: 6,
+            prestige: 6,
+            total_prosperity: 0,
+            saxon_renown: 4,
+            scotti_renown: 4,
+            briton_control_threshold: 10,
+            prosperity_plus_prestige_threshold: None,
+            control_plus_prestige_threshold: Some(18),
+            saxon_control_threshold: 5,
+            saxon_renown_threshold: Some(14),
+            scotti_renown_threshold: 12,
+        },
+        civitates_available: CivitatesAvailable {
+            militia: 11,
+            comitates: 4,
+            towns: 2,
+            hillforts: 8,
+            refugees: 2,
+        },
+        civitates_not_yet_in_play: CivitatesNotYetInPlay { comitates: 4 },
+        dux_available: DuxAvailable {
+            cavalry: 4,
+            forts: 6,
+        },
+        dux_casualties: DuxCasualties { cavalry: 2 },
+        dux_out_of_play: DuxOutOfPlay { cavalry: 7 },
+        saxon_available: SaxonsAvailable {
+            raiders: 10,
+            warbands: 21,
+            settlements: 9,
+            max_settlements: 5,
+        },
+        scotti_available: ScottiAvailable {
+            raiders: 6,
+            warbands: 15,
+            settlements: 7,
+            max_settlements: 4,
+        },
+        scotti_niall_noigiallach: ScottiNiallNoigiallach { raiders: 2 },
+        imperium: Imperium::Autonomy(Dominance::Military),
+        roads_maintained: false,
+        lasting: LastingEffects::default(),
+    };
+
+    board.setup_space(
+        LONDINIUM,
+        vec![
+            Unit::con_militia(2),
+            Unit::con_comitates(2),
+            Unit::con_cavalry(2),
+        ],
+        vec![(0, town())],
+        1,
+        1,
+    );
+    board.setup_space(
+        EBORACUM,
+        vec![Unit::con_militia(1), Unit::con_cavalry(2)],
+        vec![(0, town())],
+        1,
+        1,
+    );
+    board.setup_space(
+        ATREBATES,
+        vec![Unit::con_militia(2), Unit::con_comitates(1)],
+        vec![(0, town()), (1, town())],
+        2,
+        2,
+    );
+    board.setup_space(
+        CANTIACI,
+        vec![
+            Unit::con_militia(1),
+            Unit::con_raiders(Player::Saxons, 2),
+            Unit::con_warbands(Player::Saxons, 2),
+        ],
+        vec![(0, settlement(Player::Saxons)), (1, town())],
+        1,
+        1,
+    );
+    board.setup_space(
+        REGNI,
+        vec![Unit::con_militia(1), Unit::con_cavalry(1)],
+        vec![(0, fort()), (1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        TRINOVANTES,
+        vec![
+            Unit::con_raiders(Player::Saxons, 1),
+            Unit::con_warbands(Player::Saxons, 3),
+        ],
+        vec![(1, settlement(Player::Saxons))],
+        0,
+        1,
+    );
+    board.setup_space(
+        ICENI,
+        vec![
+            Unit::con_raiders(Player::Saxons, 2),
+            Unit::con_warbands(Player::Saxons, 2),
+        ],
+        vec![(0, settlement(Player::Saxons))],
+        0,
+        1,
+    );
+    board.setup_space(
+        CATUVELLAUNI,
+        vec![Unit::con_militia(2), Unit::con_comitates(1)],
+        vec![(0, town()), (1, town())],
+        2,
+        3,
+    );
+    board.setup_space(
+        DOBUNNI,
+        vec![Unit::con_militia(2), Unit::con_comitates(1)],
+        vec![(0, town()), (1, town())],
+        2,
+        2,
+    );
+    board.setup_space(
+        DUROTRIGES,
+        vec![Unit::con_militia(2), Unit::con_comitates(1)],
+        vec![(0, hillfort()), (1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        CORIELTAUVI,
+        vec![Unit::con_militia(1), Unit::con_cavalry(1)],
+        vec![(1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        CORNOVII,
+        vec![Unit::con_militia(1), Unit::con_comitates(1)],
+        vec![(0, hillfort()), (1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        PARISI,
+        vec![Unit::con_warbands(Player::Saxons, 2)],
+        vec![],
+        0,
+        1,
+    );
+    board.setup_space(
+        BRIGANTES,
+        vec![Unit::con_militia(1)],
+        vec![(0, hillfort()), (1, hillfort())],
+        0,
+        1,
+    );
+    board.setup_space(
+        DUMNONII,
+        vec![Unit::con_militia(1)],
+        vec![(0, hillfort())],
+        0,
+        1,
+    );
+    board.setup_space(
+        TEXTOVERDI,
+        vec![Unit::con_cavalry(1)],
+        vec![(0, fort())],
+        0,
+        1,
+    );
+    board.setup_space(
+        DEMETAE,
+        vec![
+            Unit::con_raiders(Player::Scotti, 2),
+            Unit::con_warbands(Player::Scotti, 3),
+        ],
+        vec![(0, settlement(Player::Scotti))],
+        0,
+        1,
+    );
+    board.setup_space(
+        ORDOVICES,
+        vec![
+            Unit::con_raiders(Player::Scotti, 2),
+            Unit::con_warbands(Player::Scotti, 2),
+        ],
+        vec![],
+        0,
+        1,
+    );
+    board.setup_space(
+        SILURES,
+        vec![Unit::con_militia(1)],
+        vec![(0, hillfort())],
+        0,
+        1,
+    );
+    board.setup_space(
+        VOTADINI,
+        vec![Unit::con_militia(1)],
+        vec![(0, hillfort())],
+        0,
+        1,
+    );
+    return board.finish_setup();
+}
+
+// AD 446: the groans of the Britons, with the Saxons in open revolt
+pub fn setup_de_excidio_britanniae() -> Board {
+    let mut board: Board = Board {
+        map: build_map(),
+        edge_track: EdgeTrack {
+            briton_resources: 10,
+            wealth: 5,
+            dux_resources: 5,
+            prestige: 4,
+            total_prosperity: 0,
+            saxon_renown: 8,
+            scotti_renown: 6,
+            briton_control_threshold: 9,
+            prosperity_plus_prestige_threshold: None,
+            control_plus_prestige_threshold: Some(16),
+            saxon_control_threshold: 6,
+            saxon_renown_threshold: None,
+            scotti_renown_threshold: 14,
+        },
+        civitates_available: CivitatesAvailable {
+            militia: 13,
+            comitates: 3,
+            towns: 3,
+            hillforts: 8,
+            refugees: 4,
+        },
+        civitates_not_yet_in_play: CivitatesNotYetInPlay { comitates: 2 },
+        dux_available: DuxAvailable {
+            cavalry: 3,
+            forts: 7,
+        },
+        dux_casualties: DuxCasualties { cavalry: 3 },
+        dux_out_of_play: DuxOutOfPlay { cavalry: 8 },
+        saxon_available: SaxonsAvailable {
+            raiders: 9,
+            warbands: 13,
+            settlements: 5,
+            max_settlements: 8,
+        },
+        scotti_available: ScottiAvailable {
+            raiders: 6,
+            warbands: 11,
+            settlements: 5,
+            max_settlements: 5,
+        },
+        scotti_niall_noigiallach: ScottiNiallNoigiallach { raiders: 0 },
+        imperium: Imperium::Fragmentation,
+        roads_maintained: false,
+        lasting: LastingEffects::default(),
+    };
+
+    board.setup_space(
+        LONDINIUM,
+        vec![
+            Unit::con_militia(2),
+            Unit::con_comitates(2),
+            Unit::con_cavalry(2),
+        ],
+        vec![(0, town())],
+        1,
+        1,
+    );
+    board.setup_space(
+        EBORACUM,
+        vec![
+            Unit::con_militia(1),
+            Unit::con_raiders(Player::Saxons, 1),
+            Unit::con_warbands(Player::Saxons, 2),
+        ],
+        vec![(0, town())],
+        0,
+        1,
+    );
+    board.setup_space(
+        ATREBATES,
+        vec![Unit::con_militia(2), Unit::con_comitates(2)],
+        vec![(0, town()), (1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        CANTIACI,
+        vec![
+            Unit::con_raiders(Player::Saxons, 1),
+            Unit::con_warbands(Player::Saxons, 4),
+        ],
+        vec![
+            (0, settlement(Player::Saxons)),
+            (1, settlement(Player::Saxons)),
+        ],
+        0,
+        1,
+    );
+    board.setup_space(
+        REGNI,
+        vec![Unit::con_warbands(Player::Saxons, 2)],
+        vec![(0, settlement(Player::Saxons))],
+        0,
+        1,
+    );
+    board.setup_space(
+        TRINOVANTES,
+        vec![
+            Unit::con_raiders(Player::Saxons, 1),
+            Unit::con_warbands(Player::Saxons, 3),
+        ],
+        vec![(1, settlement(Player::Saxons))],
+        0,
+        1,
+    );
+    board.setup_space(
+        ICENI,
+        vec![
+            Unit::con_raiders(Player::Saxons, 2),
+            Unit::con_warbands(Player::Saxons, 3),
+        ],
+        vec![
+            (0, settlement(Player::Saxons)),
+            (1, settlement(Player::Saxons)),
+        ],
+        0,
+        1,
+    );
+    board.setup_space(
+        PARISI,
+        vec![
+            Unit::con_raiders(Player::Saxons, 1),
+            Unit::con_warbands(Player::Saxons, 3),
+        ],
+        vec![(0, settlement(Player::Saxons))],
+        0,
+        1,
+    );
+    board.setup_space(
+        CATUVELLAUNI,
+        vec![
+            Unit::con_militia(2),
+            Unit::con_comitates(1),
+            Unit::con_cavalry(1),
+        ],
+        vec![(0, town()), (1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        DOBUNNI,
+        vec![Unit::con_militia(2), Unit::con_comitates(2)],
+        vec![(0, town()), (1, town())],
+        2,
+        2,
+    );
+    board.setup_space(
+        DUROTRIGES,
+        vec![Unit::con_militia(2), Unit::con_comitates(1)],
+        vec![(0, hillfort()), (1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        DUMNONII,
+        vec![Unit::con_militia(1), Unit::con_comitates(1)],
+        vec![(0, hillfort())],
+        0,
+        1,
+    );
+    board.setup_space(
+        CORNOVII,
+        vec![Unit::con_militia(1), Unit::con_comitates(1)],
+        vec![(0, hillfort()), (1, town())],
+        1,
+        2,
+    );
+    board.setup_space(
+        CORIELTAUVI,
+        vec![Unit::con_militia(1), Unit::con_cavalry(1)],
+        vec![(1, town())],
+        1,
+        1,
+    );
+    board.setup_space(
+        BRIGANTES,
+        vec![Unit::con_militia(1), Unit::con_cavalry(1)],
+        vec![(0, hillfort()), (1, hillfort())],
+        0,
+        1,
+    );
+    board.setup_space(
+        TEXTOVERDI,
+        vec![Unit::con_cavalry(1)],
+        vec![(0, fort())],
+        0,
+        1,
+    );
+    board.setup_space(
+        VOTADINI,
+        vec![Unit::con_militia(1)],
+        vec![(0, hillfort())],
+        0,
+        0,
+    );
+    board.setup_space(
+        SILURES,
+        vec![Unit::con_militia(1)],
+        vec![(0, hillfort()), (1, town())],
+        1,
+        1,
+    );
+    board.setup_space(
+        DEMETAE,
+        vec![
+            Unit::con_raiders(Player::Scotti, 2),
+            Unit::con_warbands(Player::Scotti, 4),
+        ],
+        vec![
+            (0, settlement(Player::Scotti)),
+            (1, settlement(Player::Scotti)),
+        ],
+        0,
+        1,
+    );
+    board.setup_space(
+        ORDOVICES,
+        vec![
+            Unit::con_raiders(Player::Scotti, 2),
+            Unit::con_warbands(Player::Scotti, 3),
+        ],
+        vec![(1, settlement(Player::Scotti))],
+        0,
+        1,
+    );
+    board.setup_space(
+        DECANGLI,
+        vec![
+            Unit::con_raiders(Player::Scotti, 2),
+            Unit::con_warbands(Player::Scotti, 2),
+        ],
+        vec![],
+        0,
+        1,
+    );
+    return board.finish_setup();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Pieces in the box; every scenario splits these between the map and the
+    // holding boxes. Not yet checked against the counter manifest, so the
+    // totals below only show that no setup loses or doubles a piece.
+    const CIVITATES_MILITIA: u8 = 30;
+    const CIVITATES_COMITATES: u8 = 15;
+    const CIVITATES_TOWNS: u8 = 15;
+    const CIVITATES_HILLFORTS: u8 = 15;
+    const DUX_CAVALRY: u8 = 20;
+    const DUX_FORTS: u8 = 8;
+    const SAXON_RAIDERS: u8 = 15;
+    const SAXON_WARBANDS: u8 = 30;
+    const SAXON_SETTLEMENTS: u8 = 12;
+    const SCOTTI_RAIDERS: u8 = 12;
+    const SCOTTI_WARBANDS: u8 = 20;
+    const SCOTTI_SETTLEMENTS: u8 = 8;
+
+    fn empty_board() -> Board {
+        Board {
+            map: build_map(),
+            edge_track: EdgeTrack {
+                briton_resources: 0,
+                wealth: 0,
+                dux_resources: 0,
+                prestige: 0,
+                total_prosperity: 0,
+                saxon_renown: 0,
+                scotti_renown: 0,
+                briton_control_threshold: 0,
+                prosperity_plus_prestige_threshold: None,
+                control_plus_prestige_threshold: None,
+                saxon_control_threshold: 0,
+                saxon_renown_threshold: None,
+                scotti_renown_threshold: 0,
+            },
+            civitates_available: CivitatesAvailable {
+                militia: 0,
+                comitates: 0,
+                towns: 0,
+                hillforts: 0,
+                refugees: 0,
+            },
+            civitates_not_yet_in_play: CivitatesNotYetInPlay { comitates: 0 },
+            dux_available: DuxAvailable {
+                cavalry: 0,
+                forts: 0,
+            },
+            dux_casualties: DuxCasualties { cavalry: 0 },
+            dux_out_of_play: DuxOutOfPlay { cavalry: 0 },
+            saxon_available: SaxonsAvailable {
+                raiders: 0,
+                warbands: 0,
+                settlements: 0,
+                max_settlements: 0,
+            },
+            scotti_available: ScottiAvailable {
+                raiders: 0,
+                warbands: 0,
+                settlements: 0,
+                max_settlements: 0,
+            },
+            scotti_niall_noigiallach: ScottiNiallNoigiallach { raiders: 0 },
+            imperium: Imperium::Fragmentation,
+            roads_maintained: false,
+            lasting: LastingEffects::default(),
+        }
+    }
+
+    #[test]
+    fn test_place_and_remove_stronghold() {
+        let mut board: Board = empty_board();
+        let town: Stronghold = Stronghold::new(StrongholdClass::Town, None, None);
+
+        assert!(board.stronghold(0, 0).is_none());
+        board.place_stronghold(0, 0, town.clone()).unwrap();
+        assert!(board.stronghold(0, 0).is_some());
+        assert_eq!(
+            board.place_stronghold(0, 0, town),
+            Err(BoardError::SiteOccupied { space: 0, site: 0 })
+        );
+
+        board.stronghold_mut(0, 0).unwrap().garrison = 3;
+        assert_eq!(board.remove_stronghold(0, 0).unwrap().garrison, 3);
+        assert!(board.stronghold(0, 0).is_none());
+        assert_eq!(
+            board.remove_stronghold(0, 0).map(|s| s.garrison),
+            Err(BoardError::SiteEmpty { space: 0, site: 0 })
+        );
+        assert_eq!(
+            board.remove_stronghold(0, 5).map(|s| s.garrison),
+            Err(BoardError::NoSuchSite { space: 0, site: 5 })
+        );
+    }
+
+    #[test]
+    fn test_cloned_board_owns_its_strongholds() {
+        let mut board: Board = empty_board();
+        board
+            .place_stronghold(0, 0, Stronghold::new(StrongholdClass::Town, None, None))
+            .unwrap();
+        let snapshot: Board = board.clone();
+
+        board.remove_stronghold(0, 0).unwrap();
+        assert!(board.stronghold(0, 0).is_none());
+        assert!(snapshot.stronghold(0, 0).is_some());
+    }
+
+    #[test]
+    fn test_move_units_updates_control() {
+        let mut board: Board = empty_board();
+        board.place_units(0, Unit::con_militia(2)).unwrap();
+        board
+            .place_units(1, Unit::con_raiders(Player::Saxons, 1))
+            .unwrap();
+        board.update_control(0).unwrap();
+        board.update_control(1).unwrap();
+        assert_eq!(board.space(1).unwrap().control, Some(Player::Saxons));
+
+        assert_eq!(
+            board
+                .move_units(0, 1, Player::Civitates, UnitClass::Militia, 3)
+                .unwrap(),
+            2
+        );
+        assert!(board.space(0).unwrap().units.is_empty());
+        assert_eq!(board.space(0).unwrap().control, None);
+        assert_eq!(board.space(1).unwrap().control, Some(Player::Civitates));
+        assert_eq!(
+            board.move_units(1, 99, Player::Civitates, UnitClass::Militia, 1),
+            Err(BoardError::NoSuchSpace(99))
+        );
+        assert_eq!(board.space(1).unwrap().units.len(), 3);
+    }
+
+    fn units_on_map(board: &Board, player: Player, class: fn(&UnitClass) -> bool) -> u8 {
+        board
+            .spaces()
+            .flat_map(|s| s.units.iter())
+            .filter(|u| u.controller == player && class(&u.designation))
+            .count() as u8
+    }
+
+    fn strongholds_on_map(
+        board: &Board,
+        class: fn(&StrongholdClass) -> bool,
+        player: Player,
+    ) -> u8 {
+        board
+            .spaces()
+            .flat_map(|s| s.stronghold_sites.iter())
+            .filter_map(|site| site.stronghold.as_ref())
+            .filter(|s| s.controller == player && class(&s.class))
+            .count() as u8
+    }
+
+    fn assert_component_totals(board: &Board) {
+        let civ = &board.civitates_available;
+        assert_eq!(
+            units_on_map(board, Player::Civitates, |c| matches!(
+                c,
+                UnitClass::Militia
+            )) + civ.militia,
+            CIVITATES_MILITIA
+        );
+        assert_eq!(
+            units_on_map(board, Player::Civitates, |c| matches!(
+                c,
+                UnitClass::Comitates
+            )) + civ.comitates
+                + board.civitates_not_yet_in_play.comitates,
+            CIVITATES_COMITATES
+        );
+        assert_eq!(
+            strongholds_on_map(
+                board,
+                |c| matches!(c, StrongholdClass::Town),
+                Player::Civitates
+            ) + civ.towns,
+            CIVITATES_TOWNS
+        );
+        assert_eq!(
+            strongholds_on_map(
+                board,
+                |c| matches!(c, StrongholdClass::Hillfort),
+                Player::Civitates
+            ) + civ.hillforts,
+            CIVITATES_HILLFORTS
+        );
+
+        assert_eq!(
+            units_on_map(board, Player::Dux, |c| matches!(c, UnitClass::Cavalry))
+                + board.dux_available.cavalry
+                + board.dux_casualties.cavalry
+                + board.dux_out_of_play.cavalry,
+            DUX_CAVALRY
+        );
+        assert_eq!(
+            strongholds_on_map(board, |c| matches!(c, StrongholdClass::Fort), Player::Dux)
+                + board.dux_available.forts,
+            DUX_FORTS
+        );
+
+        let saxons = &board.saxon_available;
+        assert_eq!(
+            units_on_map(board, Player::Saxons, |c| matches!(c, UnitClass::Raider))
+                + saxons.raiders,
+            SAXON_RAIDERS
+        );
+        assert_eq!(
+            units_on_map(board, Player::Saxons, |c| matches!(c, UnitClass::Warband))
+                + saxons.warbands,
+            SAXON_WARBANDS
+        );
+        assert_eq!(
+            strongholds_on_map(
+                board,
+                |c| matches!(c, StrongholdClass::Settlement),
+                Player::Saxons
+            ) + saxons.settlements,
+            SAXON_SETTLEMENTS
+        );
+
+        let scotti = &board.scotti_available;
+        assert_eq!(
+            units_on_map(board, Player::Scotti, |c| matches!(c, UnitClass::Raider))
+                + scotti.raiders
+                + board.scotti_niall_noigiallach.raiders,
+            SCOTTI_RAIDERS
+        );
+        assert_eq!(
+            units_on_map(board, Player::Scotti, |c| matches!(c, UnitClass::Warband))
+                + scotti.warbands,
+            SCOTTI_WARBANDS
+        );
+        assert_eq!(
+            strongholds_on_map(
+                board,
+                |c| matches!(c, StrongholdClass::Settlement),
+                Player::Scotti
+            ) + scotti.settlements,
+            SCOTTI_SETTLEMENTS
+        );
+
+        for space in board.spaces() {
+            assert_eq!(space.control, space.controlling_player());
+        }
+        assert_eq!(board.edge_track.total_prosperity, board.total_prosperity());
+    }
+
+    #[test]
+    fn test_setup_barbarian_conspiracy() {
+        let board: Board = setup_barbarian_conspiracy();
+        assert_component_totals(&board);
+        assert_eq!(
+            board.space(LONDINIUM).unwrap().control,
+            Some(Player::Civitates)
+        );
+        assert_eq!(board.space(TEXTOVERDI).unwrap().control, Some(Player::Dux));
+    }
+
+    #[test]
+    fn test_victory_margin() {
+        let mut board: Board = setup_barbarian_conspiracy();
+        // 15 Briton-controlled spaces against 12
+        assert_eq!(board.victory_margin(Player::Civitates), 3);
+        // 43 Prosperity and 10 Prestige against 36
+        assert_eq!(board.victory_margin(Player::Dux), 17);
+        // 1 space against 6, but 0 Renown against 12 is further off
+        assert_eq!(board.victory_margin(Player::Saxons), -12);
+        assert_eq!(board.victory_margin(Player::Scotti), -10);
+
+        board.shift_track(Track::SaxonRenown, 12);
+        assert_eq!(board.victory_margin(Player::Saxons), -5);
+    }
+
+    #[test]
+    fn test_setup_etty_tyrants() {
+        let board: Board = setup_etty_tyrants();
+        assert_component_totals(&board);
+        assert_eq!(
+            board.space(ORDOVICES).unwrap().control,
+            Some(Player::Scotti)
+        );
+    }
+
+    #[test]
+    fn test_setup_the_harder_they_fall() {
+        let board: Board = setup_the_harder_they_fall();
+        assert_component_totals(&board);
+        assert_eq!(
+            board.space(TRINOVANTES).unwrap().control,
+            Some(Player::Saxons)
+        );
+    }
+
+    #[test]
+    fn test_setup_de_excidio_britanniae() {
+        let board: Board = setup_de_excidio_britanniae();
+        assert_component_totals(&board);
+        assert_eq!(board.space(CANTIACI).unwrap().control, Some(Player::Saxons));
+        assert_eq!(board.space(DEMETAE).unwrap().control, Some(Player::Scotti));
     }
 }
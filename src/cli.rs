@@ -0,0 +1,174 @@
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+use super::concepts::Player;
+use super::setup::Scenario;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "pdg-rust-cli",
+    version,
+    about = "Pendragon: The Fall of Roman Britain at the terminal",
+    args_conflicts_with_subcommands = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub play: PlayArgs,
+
+    /// Print everything without colour
+    #[arg(long, global = true)]
+    pub no_color: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Play a game (the default when no command is given)
+    Play(PlayArgs),
+    /// Replay a decision log and check it reaches the state it was written in
+    Replay {
+        /// Decision log written at the end of a game
+        log: PathBuf,
+    },
+    /// Play bots against each other without asking anything
+    Simulate(SimulateArgs),
+}
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct PlayArgs {
+    /// barbarian-conspiracy, petty-tyrants, the-harder-they-fall or
+    /// de-excidio-britanniae; asked for if not given
+    #[arg(long, conflicts_with = "load")]
+    pub scenario: Option<Scenario>,
+
+    /// Seed for the deck and everything else left to chance
+    #[arg(long, conflicts_with = "load")]
+    pub seed: Option<u64>,
+
+    /// Factions played at the keyboard, e.g. --humans dux,civitates. The
+    /// rest are bots.
+    #[arg(long, value_delimiter = ',')]
+    pub humans: Option<Vec<Player>>,
+
+    /// Factions played by bots. The rest are played at the keyboard.
+    #[arg(long, value_delimiter = ',')]
+    pub bots: Option<Vec<Player>>,
+
+    /// Resume a saved game
+    #[arg(long, value_name = "SAVE")]
+    pub load: Option<PathBuf>,
+
+    /// How many cards back undo may reach; 0 is the current card only
+    #[arg(long, default_value_t = 0, value_name = "CARDS")]
+    pub undo_cards: usize,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct SimulateArgs {
+    #[arg(long)]
+    pub scenario: Scenario,
+
+    /// Seed of the first game; each game after it adds one
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// How many games to play
+    #[arg(long, default_value_t = 1)]
+    pub games: u32,
+}
+
+impl PlayArgs {
+    // Who is at the keyboard, once the two lists are known not to disagree.
+    // With neither list everyone is.
+    pub fn humans(&self) -> Result<Vec<Player>, String> {
+        let humans: Vec<Player> = match (&self.humans, &self.bots) {
+            (None, None) => Player::ALL.to_vec(),
+            (Some(humans), None) => humans.clone(),
+            (None, Some(bots)) => Player::ALL
+                .into_iter()
+                .filter(|p| !bots.contains(p))
+                .collect(),
+            (Some(humans), Some(bots)) => {
+                if let Some(both) = humans.iter().find(|p| bots.contains(p)) {
+                    return Err(format!("{:?} cannot be both human and a bot", both));
+                }
+                if let Some(neither) = Player::ALL
+                    .iter()
+                    .find(|p| !humans.contains(p) && !bots.contains(p))
+                {
+                    return Err(format!("{:?} is neither human nor a bot", neither));
+                }
+                humans.clone()
+            }
+        };
+        return Ok(humans);
+    }
+}
+
+impl SimulateArgs {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.games == 0 {
+            return Err(String::from("--games must be at least 1"));
+        }
+        if self
+            .seed
+            .is_some_and(|seed| seed.checked_add(self.games as u64 - 1).is_none())
+        {
+            return Err(String::from("--seed is too large for that many games"));
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("pdg-rust-cli").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_play_is_the_default() {
+        let cli: Cli = parse(&["--scenario", "petty-tyrants", "--seed", "4"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.play.scenario, Some(Scenario::EttyTyrants));
+        assert_eq!(cli.play.humans().unwrap(), Player::ALL.to_vec());
+
+        let cli: Cli = parse(&["play", "--bots", "saxons,scotti", "--no-color"]).unwrap();
+        assert!(cli.no_color);
+        let Some(Command::Play(play)) = cli.command else {
+            panic!("Expected play");
+        };
+        assert_eq!(play.humans().unwrap(), vec![Player::Civitates, Player::Dux]);
+    }
+
+    #[test]
+    fn test_invalid_combinations() {
+        assert!(parse(&["--load", "a.toml", "--seed", "1"]).is_err());
+        assert!(parse(&["--scenario", "camelot"]).is_err());
+        assert!(parse(&["--seed", "1", "replay", "log.toml"]).is_err());
+        assert!(parse(&["simulate"]).is_err());
+
+        let cli: Cli = parse(&["--humans", "dux", "--bots", "dux,saxons"]).unwrap();
+        assert!(cli.play.humans().is_err());
+        let cli: Cli = parse(&["--humans", "dux", "--bots", "saxons"]).unwrap();
+        assert!(cli.play.humans().is_err());
+
+        let Some(Command::Simulate(sim)) = parse(&[
+            "simulate",
+            "--scenario",
+            "barbarian-conspiracy",
+            "--games",
+            "0",
+        ])
+        .unwrap()
+        .command
+        else {
+            panic!("Expected simulate");
+        };
+        assert!(sim.validate().is_err());
+    }
+}
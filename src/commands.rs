@@ -1,133 +1,1824 @@
-use super::board::{Space, StrongholdSite, StrongholdSiteType};
-use super::concepts::{CivitatesHolding, Player, StrongholdClass, Unit};
-use dialoguer::Input;
-
-// TODO: func for selecting spaces
-// TODO: Muster (and other commands) as state machine?
-// States would be selecting spaces and spending, type of muster
-// But need to allow for feats as well
-pub fn muster(loc: Space, wealth: u8, avail: CivitatesHolding) -> (Space, u8) {
-    let resulting_loc: Space;
-    let w: u8;
-    if true {
-        (resulting_loc, w) = muster_units(loc, wealth, avail);
-    } else {
-        (resulting_loc, w) = muster_units(loc, wealth, avail);
-        // resulting_loc = muster_strongholds(loc);
+use super::battle::{self, Dice};
+use super::board::{Board, BoardError, Space, Track};
+use super::concepts::{Player, StrongholdClass, UnitClass};
+use super::decisions::{Decision, DecisionProvider, Interrupt};
+use super::narrate::narrate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommandType {
+    Muster,
+    March,
+    Trade,
+    Train,
+    Intercept,
+    Raid,
+    Return,
+    Battle,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FeatType {
+    Rule,
+    Invite,
+    Reinforce,
+    Pillage,
+    Build,
+    Requisition,
+    Retaliate,
+    Settle,
+    Surprise,
+    Ravage,
+    Shieldwall,
+    Ransom,
+    Entreat,
+}
+
+impl fmt::Display for CommandType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl fmt::Display for FeatType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
     }
-    return (resulting_loc, w);
 }
 
-// TODO: Check available when adding units
-fn muster_units(loc: Space, wealth: u8, avail: CivitatesHolding) -> (Space, u8) {
-    let mut resulting_loc: Space<'_> = loc.clone();
-    let mut cubes_to_place = 0;
+// The commands execute carries out so far. The rest of each faction's
+// player aid is still to be written and is not offered.
+const IMPLEMENTED_COMMANDS: [(Player, CommandType); 5] = [
+    (Player::Civitates, CommandType::Muster),
+    (Player::Civitates, CommandType::March),
+    (Player::Civitates, CommandType::Trade),
+    (Player::Civitates, CommandType::Battle),
+    (Player::Dux, CommandType::Train),
+];
 
-    for stronghold_site in loc.stronghold_sites {
-        match stronghold_site.stronghold {
-            Some(s) => match s.class {
-                StrongholdClass::Hillfort => cubes_to_place += 1,
-                StrongholdClass::Town => cubes_to_place += 1,
-                _ => {}
-            },
-            None => {}
+// No Feat is carried out yet
+const IMPLEMENTED_FEATS: [(Player, FeatType); 0] = [];
+
+impl CommandType {
+    // Each faction's commands, in player aid order
+    pub fn of(player: Player) -> &'static [CommandType] {
+        match player {
+            Player::Civitates => &[
+                CommandType::Muster,
+                CommandType::March,
+                CommandType::Trade,
+                CommandType::Battle,
+            ],
+            Player::Dux => &[
+                CommandType::Train,
+                CommandType::March,
+                CommandType::Intercept,
+                CommandType::Battle,
+            ],
+            Player::Saxons | Player::Scotti => &[
+                CommandType::Raid,
+                CommandType::Return,
+                CommandType::March,
+                CommandType::Battle,
+            ],
+        }
+    }
+
+    // The faction's commands that can be carried out, in player aid order
+    pub fn implemented(player: Player) -> Vec<CommandType> {
+        CommandType::of(player)
+            .iter()
+            .copied()
+            .filter(|c| IMPLEMENTED_COMMANDS.contains(&(player, *c)))
+            .collect()
+    }
+}
+
+impl FeatType {
+    pub fn of(player: Player) -> &'static [FeatType] {
+        match player {
+            Player::Civitates => &[
+                FeatType::Rule,
+                FeatType::Invite,
+                FeatType::Reinforce,
+                FeatType::Pillage,
+            ],
+            Player::Dux => &[
+                FeatType::Build,
+                FeatType::Invite,
+                FeatType::Requisition,
+                FeatType::Retaliate,
+            ],
+            Player::Saxons => &[
+                FeatType::Settle,
+                FeatType::Surprise,
+                FeatType::Ravage,
+                FeatType::Shieldwall,
+            ],
+            Player::Scotti => &[
+                FeatType::Settle,
+                FeatType::Surprise,
+                FeatType::Ransom,
+                FeatType::Entreat,
+            ],
         }
     }
 
-    match loc.control {
-        Some(p) => {
-            if p == Player::Civitates {
-                cubes_to_place += loc.pop;
+    // The faction's feats that can be carried out
+    pub fn implemented(player: Player) -> Vec<FeatType> {
+        FeatType::of(player)
+            .iter()
+            .copied()
+            .filter(|f| IMPLEMENTED_FEATS.contains(&(player, *f)))
+            .collect()
+    }
+}
+
+// Anything that stops a command or feat going ahead. A command may already
+// have paid, moved pieces or rolled dice when one of these turns up, so the
+// caller has to put the board back; Game::step does so for every step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommandError {
+    Board(BoardError),
+    Interrupted(Interrupt),
+    AnswerOutOfRange {
+        answer: usize,
+        answers: usize,
+    },
+    NotFactionCommand {
+        player: Player,
+        command: CommandType,
+    },
+    NotFactionFeat {
+        player: Player,
+        feat: FeatType,
+    },
+    // On the faction's player aid but not written yet
+    CommandNotImplemented {
+        player: Player,
+        command: CommandType,
+    },
+    FeatNotImplemented {
+        player: Player,
+        feat: FeatType,
+    },
+    // A Limited Command is one space and nothing more
+    FeatWithLimited(FeatType),
+    TooManySpaces {
+        chosen: usize,
+    },
+    CannotAfford {
+        cost: u8,
+        resources: u8,
+    },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Board(e) => write!(f, "{}", e),
+            CommandError::Interrupted(interrupt) => write!(f, "Interrupted by {:?}", interrupt),
+            CommandError::AnswerOutOfRange { answer, answers } => write!(
+                f,
+                "Answered {} but there are only {} options",
+                answer, answers
+            ),
+            CommandError::NotFactionCommand { player, command } => {
+                write!(f, "{} cannot {}", player, command)
+            }
+            CommandError::NotFactionFeat { player, feat } => {
+                write!(f, "{} cannot {}", player, feat)
             }
+            CommandError::CommandNotImplemented { player, command } => {
+                write!(f, "{} {} can't be carried out yet", player, command)
+            }
+            CommandError::FeatNotImplemented { player, feat } => {
+                write!(f, "{} {} can't be carried out yet", player, feat)
+            }
+            CommandError::FeatWithLimited(feat) => {
+                write!(f, "{} cannot go with a Limited Command", feat)
+            }
+            CommandError::TooManySpaces { chosen } => write!(
+                f,
+                "A Limited Command takes exactly one space, not {}",
+                chosen
+            ),
+            CommandError::CannotAfford { cost, resources } => write!(
+                f,
+                "That costs {} but there are only {} Resources",
+                cost, resources
+            ),
         }
-        None => {}
     }
+}
 
-    println!(
-        "Placing {} cubes. Place Comitates instead of Militia?\nEach Comitates costs 1 Wealth.\nCurrent Wealth: {}",
-        cubes_to_place, wealth
+impl From<BoardError> for CommandError {
+    fn from(e: BoardError) -> Self {
+        CommandError::Board(e)
+    }
+}
+
+impl From<Interrupt> for CommandError {
+    fn from(interrupt: Interrupt) -> Self {
+        CommandError::Interrupted(interrupt)
+    }
+}
+
+fn pick(
+    player: Player,
+    prompt: &str,
+    options: &[String],
+    decisions: &mut dyn DecisionProvider,
+) -> Result<usize, CommandError> {
+    let answer: usize = decisions.decide(player, &Decision::Choice { prompt, options }, None)?;
+    if answer >= options.len() {
+        return Err(CommandError::AnswerOutOfRange {
+            answer,
+            answers: options.len(),
+        });
+    }
+    return Ok(answer);
+}
+
+fn count(
+    player: Player,
+    prompt: &str,
+    max: u8,
+    decisions: &mut dyn DecisionProvider,
+) -> Result<u8, CommandError> {
+    let decision: Decision = Decision::Count { prompt, max };
+    let answer: usize = decisions.decide(player, &decision, None)?;
+    if answer >= decision.answers() {
+        return Err(CommandError::AnswerOutOfRange {
+            answer,
+            answers: decision.answers(),
+        });
+    }
+    return Ok(answer as u8);
+}
+
+const DONE: &str = "Done";
+
+// A command paid for space by space out of one of the tracks. The base
+// cost is looked up per space, as some commands cost more in one space than
+// in another. A free command, as events grant, costs nothing anywhere.
+#[derive(Clone, Copy, Debug)]
+pub struct Payment {
+    pub track: Track,
+    pub command: CommandType,
+    pub base: fn(&Board, u8) -> u8,
+    pub free: bool,
+}
+
+impl Payment {
+    pub fn total(&self, board: &Board, player: Player, spaces: &[u8]) -> u8 {
+        if self.free {
+            return 0;
+        }
+        spaces
+            .iter()
+            .map(|id| cost_in(board, player, self.command, *id, (self.base)(board, *id)))
+            .sum()
+    }
+}
+
+// Asks for spaces one at a time until the player is done. A Limited Command
+// takes exactly one, so there is no stopping early and no second choice.
+// Candidates are offered in id order so logged answers replay the same.
+pub fn select_spaces(
+    player: Player,
+    prompt: &str,
+    board: &Board,
+    candidates: Vec<u8>,
+    limited: bool,
+    decisions: &mut dyn DecisionProvider,
+) -> Result<Vec<u8>, CommandError> {
+    select(player, None, prompt, board, candidates, limited, decisions)
+}
+
+// As select_spaces, except that a space is no longer offered once what has
+// been chosen leaves too little to pay for it
+pub fn select_paid_spaces(
+    player: Player,
+    payment: Payment,
+    prompt: &str,
+    board: &Board,
+    candidates: Vec<u8>,
+    limited: bool,
+    decisions: &mut dyn DecisionProvider,
+) -> Result<Vec<u8>, CommandError> {
+    select(
+        player,
+        Some(payment),
+        prompt,
+        board,
+        candidates,
+        limited,
+        decisions,
+    )
+}
+
+fn select(
+    player: Player,
+    payment: Option<Payment>,
+    prompt: &str,
+    board: &Board,
+    mut candidates: Vec<u8>,
+    limited: bool,
+    decisions: &mut dyn DecisionProvider,
+) -> Result<Vec<u8>, CommandError> {
+    candidates.sort();
+    let mut chosen: Vec<u8> = vec![];
+    let most: usize = if limited { 1 } else { candidates.len() };
+    while chosen.len() < most {
+        if let Some(payment) = payment {
+            let left: u8 = board
+                .track(payment.track)
+                .saturating_sub(payment.total(board, player, &chosen));
+            candidates.retain(|id| payment.total(board, player, &[*id]) <= left);
+        }
+        if candidates.is_empty() {
+            break;
+        }
+        let mut options: Vec<String> = candidates
+            .iter()
+            .map(|id| board.space(*id).map_or(id.to_string(), |s| s.name.clone()))
+            .collect();
+        if !limited && !chosen.is_empty() {
+            options.push(String::from(DONE));
+        }
+        let answer: usize = pick(player, prompt, &options, decisions)?;
+        if answer == candidates.len() {
+            break;
+        }
+        chosen.push(candidates.remove(answer));
+    }
+    return Ok(chosen);
+}
+
+// Where pieces may come from to reach the destinations: any space next to
+// one of them. A Limited Command has a single destination, and only that
+// destination's neighbours can send pieces to it.
+pub fn origins(board: &Board, destinations: &[u8], limited: bool) -> Result<Vec<u8>, CommandError> {
+    if limited && destinations.len() > 1 {
+        return Err(CommandError::TooManySpaces {
+            chosen: destinations.len(),
+        });
+    }
+    let mut origins: Vec<u8> = vec![];
+    for destination in destinations {
+        let space: &Space = board
+            .space(*destination)
+            .ok_or(BoardError::NoSuchSpace(*destination))?;
+        for adjacent in &space.adj_spaces {
+            if !origins.contains(adjacent) && !destinations.contains(adjacent) {
+                origins.push(*adjacent);
+            }
+        }
+    }
+    return Ok(origins);
+}
+
+// Carries out one command. `limited` is set by the sequence of play for a
+// Limited Command and holds the command to a single space.
+pub fn execute(
+    player: Player,
+    command: CommandType,
+    board: &mut Board,
+    dice: &mut Dice,
+    decisions: &mut dyn DecisionProvider,
+    limited: bool,
+    free: bool,
+) -> Result<(), CommandError> {
+    if !CommandType::of(player).contains(&command) {
+        return Err(CommandError::NotFactionCommand { player, command });
+    }
+    match (player, command) {
+        (Player::Civitates, CommandType::Muster) => {
+            civitates_muster(board, decisions, limited, free)
+        }
+        (Player::Civitates, CommandType::March) => civitates_march(board, decisions, limited, free),
+        (Player::Civitates, CommandType::Trade) => civitates_trade(board, decisions, limited),
+        (Player::Civitates, CommandType::Battle) => {
+            civitates_battle(board, dice, decisions, limited, free)
+        }
+        (Player::Dux, CommandType::Train) => dux_train(board, decisions, limited, free),
+        _ => Err(CommandError::CommandNotImplemented { player, command }),
+    }
+}
+
+// Feats only ever come with a full Command
+pub fn feat(
+    player: Player,
+    feat: FeatType,
+    _board: &mut Board,
+    _decisions: &mut dyn DecisionProvider,
+    limited: bool,
+) -> Result<(), CommandError> {
+    if limited {
+        return Err(CommandError::FeatWithLimited(feat));
+    }
+    if !FeatType::of(player).contains(&feat) {
+        return Err(CommandError::NotFactionFeat { player, feat });
+    }
+    return Err(CommandError::FeatNotImplemented { player, feat });
+}
+
+// What a command costs in one space, after any Capability or Momentum
+pub fn cost_in(board: &Board, player: Player, command: CommandType, space: u8, base: u8) -> u8 {
+    let delta: i8 = board
+        .lasting()
+        .command_cost_delta(player, command, Some(space));
+    return base.saturating_add_signed(delta);
+}
+
+// Takes the whole cost up front, so a command the faction cannot pay for
+// never starts
+fn pay(board: &mut Board, track: Track, cost: u8) -> Result<(), CommandError> {
+    let resources: u8 = board.track(track);
+    if cost > resources {
+        return Err(CommandError::CannotAfford { cost, resources });
+    }
+    board.shift_track(track, -(cost as i8));
+    return Ok(());
+}
+
+const MUSTER_COST: u8 = 1;
+
+// Each Mustering space either gets units or one new stronghold
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MusterMode {
+    Units,
+    Stronghold(StrongholdClass),
+}
+
+impl fmt::Display for MusterMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MusterMode::Units => write!(f, "Militia and Comitates"),
+            MusterMode::Stronghold(class) => write!(f, "A {:?}", class),
+        }
+    }
+}
+
+// One unit for each Town and Hillfort, and the Population as well where the
+// Civitates control the space
+fn muster_units_in(space: &Space) -> u8 {
+    let mut units: u8 = space.strongholds_of(StrongholdClass::Town)
+        + space.strongholds_of(StrongholdClass::Hillfort);
+    if space.control == Some(Player::Civitates) {
+        units += space.pop;
+    }
+    return units;
+}
+
+// What can be Mustered in a space with what the Civitates have left.
+// Strongholds go only where the Civitates already control the space.
+pub fn muster_modes(board: &Board, space: &Space) -> Vec<MusterMode> {
+    let mut modes: Vec<MusterMode> = vec![];
+    let units: u8 = board.available_units(Player::Civitates, UnitClass::Militia)
+        + board.available_units(Player::Civitates, UnitClass::Comitates);
+    if muster_units_in(space) > 0 && units > 0 {
+        modes.push(MusterMode::Units);
+    }
+    if space.control == Some(Player::Civitates) {
+        for class in [StrongholdClass::Town, StrongholdClass::Hillfort] {
+            if space.empty_site_for(class).is_some()
+                && board.available_strongholds(Player::Civitates, class) > 0
+            {
+                modes.push(MusterMode::Stronghold(class));
+            }
+        }
+    }
+    return modes;
+}
+
+// Civitates Muster where they control the space or hold a Town or Hillfort,
+// paying Briton Resources for each space
+fn civitates_muster(
+    board: &mut Board,
+    decisions: &mut dyn DecisionProvider,
+    limited: bool,
+    free: bool,
+) -> Result<(), CommandError> {
+    let payment: Payment = Payment {
+        track: Track::BritonResources,
+        command: CommandType::Muster,
+        base: |_, _| MUSTER_COST,
+        free,
+    };
+    let candidates: Vec<u8> = board
+        .spaces()
+        .filter(|s| !muster_modes(board, s).is_empty())
+        .map(|s| s.id)
+        .collect();
+    let spaces: Vec<u8> = select_paid_spaces(
+        Player::Civitates,
+        payment,
+        "Muster in which space?",
+        board,
+        candidates,
+        limited,
+        decisions,
+    )?;
+    // Each space is paid for once something is placed there
+    for id in spaces {
+        let cost: u8 = payment.total(board, Player::Civitates, &[id]);
+        if muster(board, id, decisions)? > 0 {
+            pay(board, payment.track, cost)?;
+        }
+    }
+    return Ok(());
+}
+
+// Musters one space, asking how when there is more than one way, and says
+// how many pieces were placed
+pub fn muster(
+    board: &mut Board,
+    id: u8,
+    decisions: &mut dyn DecisionProvider,
+) -> Result<u8, CommandError> {
+    let space: &Space = board.space(id).ok_or(BoardError::NoSuchSpace(id))?;
+    let modes: Vec<MusterMode> = muster_modes(board, space);
+    let mode: MusterMode = match modes.len() {
+        0 => {
+            narrate!("Nothing left to Muster in {}", space.name);
+            return Ok(0);
+        }
+        1 => modes[0],
+        _ => {
+            let options: Vec<String> = modes.iter().map(|m| m.to_string()).collect();
+            let prompt: String = format!("Muster what in {}?", space.name);
+            modes[pick(Player::Civitates, &prompt, &options, decisions)?]
+        }
+    };
+    match mode {
+        MusterMode::Units => muster_units(board, id, decisions),
+        MusterMode::Stronghold(class) => {
+            board.place_stronghold_from_available(id, Player::Civitates, class)?;
+            let space: &Space = board.space(id).ok_or(BoardError::NoSuchSpace(id))?;
+            narrate!("Placed a {:?} in {}", class, space.name);
+            Ok(1)
+        }
+    }
+}
+
+// Militia by default, with any of them swapped for Comitates at 1 Wealth
+// each. Only as many as are Available are placed.
+fn muster_units(
+    board: &mut Board,
+    id: u8,
+    decisions: &mut dyn DecisionProvider,
+) -> Result<u8, CommandError> {
+    let space: &Space = board.space(id).ok_or(BoardError::NoSuchSpace(id))?;
+    let militia: u8 = board.available_units(Player::Civitates, UnitClass::Militia);
+    let comitates: u8 = board.available_units(Player::Civitates, UnitClass::Comitates);
+    let wealth: u8 = board.track(Track::Wealth);
+    let units: u8 = muster_units_in(space).min(militia + comitates.min(wealth));
+    narrate!(
+        "Placing {} units in {}. Each Comitates instead of a Militia costs 1 Wealth.\nCurrent Wealth: {}",
+        units,
+        space.name,
+        wealth
     );
-    let mut num_com: Result<u8, std::num::ParseIntError>;
+    let swapped: u8 = count(
+        Player::Civitates,
+        "Enter number of Comitates to place instead",
+        units.min(comitates).min(wealth),
+        decisions,
+    )?;
+    let wanted: u8 = units - swapped;
+    if wanted > militia {
+        narrate!(
+            "Only {} Militia Available, so {} units are placed rather than {}",
+            militia,
+            swapped + militia,
+            units
+        );
+    }
+    board.shift_track(Track::Wealth, -(swapped as i8));
+    let placed: u8 =
+        board.place_from_available(id, Player::Civitates, UnitClass::Comitates, swapped)?
+            + board.place_from_available(
+                id,
+                Player::Civitates,
+                UnitClass::Militia,
+                wanted.min(militia),
+            )?;
+    narrate!("Placed {} units, {} of them Comitates", placed, swapped);
+    return Ok(placed);
+}
+
+const MARCH_COST: u8 = 1;
+
+const MARCHING: [UnitClass; 2] = [UnitClass::Comitates, UnitClass::Militia];
+
+fn marching_units(space: &Space) -> u8 {
+    MARCHING
+        .iter()
+        .map(|class| space.units_of(Player::Civitates, *class))
+        .sum()
+}
+
+// Civitates March out of the origins they pay for into the spaces next to
+// them. A Limited March picks its one destination first, and only the
+// spaces next to it can be origins.
+fn civitates_march(
+    board: &mut Board,
+    decisions: &mut dyn DecisionProvider,
+    limited: bool,
+    free: bool,
+) -> Result<(), CommandError> {
+    let payment: Payment = Payment {
+        track: Track::BritonResources,
+        command: CommandType::March,
+        base: |_, _| MARCH_COST,
+        free,
+    };
+    let resources: u8 = board.track(Track::BritonResources);
+    let can_march = |board: &Board, id: u8| -> bool {
+        board.space(id).is_some_and(|s| {
+            marching_units(s) > 0 && payment.total(board, Player::Civitates, &[id]) <= resources
+        })
+    };
+    let destination: Option<u8> = if limited {
+        let candidates: Vec<u8> = board
+            .spaces()
+            .filter(|s| s.adj_spaces.iter().any(|a| can_march(board, *a)))
+            .map(|s| s.id)
+            .collect();
+        let chosen: Vec<u8> = select_spaces(
+            Player::Civitates,
+            "March to which space?",
+            board,
+            candidates,
+            true,
+            decisions,
+        )?;
+        let Some(destination) = chosen.first() else {
+            narrate!("The Civitates have nowhere to March");
+            return Ok(());
+        };
+        Some(*destination)
+    } else {
+        None
+    };
+    let candidates: Vec<u8> = match destination {
+        Some(destination) => origins(board, &[destination], true)?,
+        None => board.spaces().map(|s| s.id).collect(),
+    }
+    .into_iter()
+    .filter(|id| can_march(board, *id))
+    .collect();
+    let from: Vec<u8> = select_paid_spaces(
+        Player::Civitates,
+        payment,
+        "March from which space?",
+        board,
+        candidates,
+        false,
+        decisions,
+    )?;
+    pay(
+        board,
+        payment.track,
+        payment.total(board, Player::Civitates, &from),
+    )?;
+    let mut arrived: BTreeMap<(u8, UnitClass), u8> = BTreeMap::new();
+    for origin in from {
+        march_from(board, origin, destination, &mut arrived, decisions)?;
+    }
+    return Ok(());
+}
+
+// Units that have already marched into a space this command stay there
+fn left_to_march(space: &Space, class: UnitClass, arrived: &BTreeMap<(u8, UnitClass), u8>) -> u8 {
+    space
+        .units_of(Player::Civitates, class)
+        .saturating_sub(arrived.get(&(space.id, class)).copied().unwrap_or(0))
+}
+
+// Sends the units in one origin off to its neighbours, a destination at a
+// time, until they have all gone or the player is done
+fn march_from(
+    board: &mut Board,
+    origin: u8,
+    destination: Option<u8>,
+    arrived: &mut BTreeMap<(u8, UnitClass), u8>,
+    decisions: &mut dyn DecisionProvider,
+) -> Result<(), CommandError> {
     loop {
-        let comitates_to_place: String = Input::new()
-            .allow_empty(true)
-            .with_prompt("Enter number of Comitates to place instead (default: 0)")
-            .interact()
-            .unwrap();
-        if comitates_to_place == "" {
-            num_com = Ok(0);
-        } else {
-            num_com = comitates_to_place.parse::<u8>();
-        }
-        match num_com {
-            Ok(n) => {
-                if n > cubes_to_place {
-                    println!(
-                        "Error: tried placing {} Comitates but there are only {} cubes to place",
-                        n, cubes_to_place
-                    );
-                }
-                if n > wealth {
-                    println!(
-                        "Error: tried placing {} Comitates but may only spend {} Wealth",
-                        n, wealth
-                    );
-                } else {
-                    println!("Placed {} Comitates", num_com.unwrap());
-                    break;
+        let space: &Space = board.space(origin).ok_or(BoardError::NoSuchSpace(origin))?;
+        if MARCHING
+            .iter()
+            .all(|class| left_to_march(space, *class, arrived) == 0)
+        {
+            return Ok(());
+        }
+        let to: u8 = match destination {
+            Some(destination) => destination,
+            None => {
+                let neighbours: Vec<u8> = space
+                    .adj_spaces
+                    .iter()
+                    .copied()
+                    .filter(|id| board.space(*id).is_some())
+                    .collect();
+                let mut options: Vec<String> = neighbours
+                    .iter()
+                    .map(|id| board.space(*id).unwrap().name.clone())
+                    .collect();
+                options.push(String::from(DONE));
+                let prompt: String = format!("March from {} to which space?", space.name);
+                let answer: usize = pick(Player::Civitates, &prompt, &options, decisions)?;
+                match neighbours.get(answer) {
+                    Some(to) => *to,
+                    None => return Ok(()),
                 }
             }
-            _ => println!("Invalid input, must enter a non-negative integer"),
+        };
+        let name: String = board.space(to).unwrap().name.clone();
+        for class in MARCHING {
+            let here: u8 = left_to_march(
+                board.space(origin).ok_or(BoardError::NoSuchSpace(origin))?,
+                class,
+                arrived,
+            );
+            if here == 0 {
+                continue;
+            }
+            let prompt: String = format!("How many {:?} march to {}?", class, name);
+            let marching: u8 = count(Player::Civitates, &prompt, here, decisions)?;
+            let moved: u8 = board.move_units(origin, to, Player::Civitates, class, marching)?;
+            if moved > 0 {
+                narrate!("{} {:?} marched to {}", moved, class, name);
+                *arrived.entry((to, class)).or_default() += moved;
+            }
+        }
+        if destination.is_some() {
+            return Ok(());
+        }
+    }
+}
+
+// What one space brings in when the Civitates Trade there
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Income {
+    pub wealth: u8,
+    pub resources: u8,
+}
+
+// The Civitates Trade where they control the space and hold a Town in it.
+// This and the income below follow the request for the command, not the
+// rulebook, which is not to hand; no sea or patrol condition is applied
+// because none could be checked against the rules.
+fn can_trade(space: &Space) -> bool {
+    space.control == Some(Player::Civitates) && space.strongholds_of(StrongholdClass::Town) > 0
+}
+
+// Town prosperity turns into Wealth and rural prosperity into Resources
+pub fn trade_income(space: &Space) -> Income {
+    return Income {
+        wealth: space.top_prosp,
+        resources: space.bottom_prosp,
+    };
+}
+
+// Civitates Trade in spaces of their choosing, seeing what they would gain
+// before they commit to it
+fn civitates_trade(
+    board: &mut Board,
+    decisions: &mut dyn DecisionProvider,
+    limited: bool,
+) -> Result<(), CommandError> {
+    let candidates: Vec<u8> = board
+        .spaces()
+        .filter(|s| can_trade(s))
+        .map(|s| s.id)
+        .collect();
+    let income: Income = loop {
+        let spaces: Vec<u8> = select_spaces(
+            Player::Civitates,
+            "Trade in which space?",
+            board,
+            candidates.clone(),
+            limited,
+            decisions,
+        )?;
+        if spaces.is_empty() {
+            narrate!("The Civitates have nowhere to Trade");
+            return Ok(());
+        }
+        let mut total: Income = Income {
+            wealth: 0,
+            resources: 0,
+        };
+        narrate!("Trading would bring in");
+        for id in &spaces {
+            let space: &Space = board.space(*id).ok_or(BoardError::NoSuchSpace(*id))?;
+            let income: Income = trade_income(space);
+            narrate!(
+                "  {}: +{} Wealth, +{} Resources",
+                space.name,
+                income.wealth,
+                income.resources
+            );
+            total.wealth = total.wealth.saturating_add(income.wealth);
+            total.resources = total.resources.saturating_add(income.resources);
+        }
+        let options: [String; 2] = [String::from("Trade"), String::from("Choose again")];
+        let prompt: String = format!(
+            "Trade for {} Wealth and {} Resources?",
+            total.wealth, total.resources
+        );
+        if pick(Player::Civitates, &prompt, &options, decisions)? == 0 {
+            break total;
+        }
+    };
+    // Tracks stop at their ends, so an income past what an i8 holds is
+    // as good as the most it can be
+    board.shift_track(
+        Track::Wealth,
+        i8::try_from(income.wealth).unwrap_or(i8::MAX),
+    );
+    board.shift_track(
+        Track::BritonResources,
+        i8::try_from(income.resources).unwrap_or(i8::MAX),
+    );
+    return Ok(());
+}
+
+const BATTLE_COST: u8 = 1;
+
+const BARBARIANS: [Player; 2] = [Player::Saxons, Player::Scotti];
+
+// Barbarians the Civitates could fight in a space: any with units or a
+// Settlement there, so an undefended Settlement can be assaulted too
+fn enemies_in(space: &Space) -> Vec<Player> {
+    BARBARIANS
+        .into_iter()
+        .filter(|p| space.pieces(*p) > 0)
+        .collect()
+}
+
+// Civitates Battle wherever their Militia or Comitates stand with
+// barbarians, paying Briton Resources for each space. The cost, like the
+// battle itself (see battle.rs), has not been checked against the rulebook.
+fn civitates_battle(
+    board: &mut Board,
+    dice: &mut Dice,
+    decisions: &mut dyn DecisionProvider,
+    limited: bool,
+    free: bool,
+) -> Result<(), CommandError> {
+    let payment: Payment = Payment {
+        track: Track::BritonResources,
+        command: CommandType::Battle,
+        base: |_, _| BATTLE_COST,
+        free,
+    };
+    let candidates: Vec<u8> = board
+        .spaces()
+        .filter(|s| marching_units(s) > 0 && !enemies_in(s).is_empty())
+        .map(|s| s.id)
+        .collect();
+    let spaces: Vec<u8> = select_paid_spaces(
+        Player::Civitates,
+        payment,
+        "Battle in which space?",
+        board,
+        candidates,
+        limited,
+        decisions,
+    )?;
+    pay(
+        board,
+        payment.track,
+        payment.total(board, Player::Civitates, &spaces),
+    )?;
+    for id in spaces {
+        let space: &Space = board.space(id).ok_or(BoardError::NoSuchSpace(id))?;
+        let enemies: Vec<Player> = enemies_in(space);
+        let defender: Player = match enemies.len() {
+            1 => enemies[0],
+            _ => {
+                let options: Vec<String> = enemies.iter().map(|p| format!("{:?}", p)).collect();
+                let prompt: String = format!("Battle whom in {}?", space.name);
+                enemies[pick(Player::Civitates, &prompt, &options, decisions)?]
+            }
+        };
+        battle::battle(board, id, Player::Civitates, defender, dice)?;
+    }
+    return Ok(());
+}
+
+const TRAIN_TROOPS_COST: u8 = 3;
+const TRAIN_PROSPERITY_COST: u8 = 2;
+
+// Each Training space either raises troops at a Fort or has its Prosperity
+// raised by 1
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrainMode {
+    Troops,
+    Prosperity,
+}
+
+impl TrainMode {
+    fn cost(self) -> u8 {
+        match self {
+            TrainMode::Troops => TRAIN_TROOPS_COST,
+            TrainMode::Prosperity => TRAIN_PROSPERITY_COST,
         }
     }
+}
 
-    resulting_loc
-        .units
-        .append(&mut Unit::con_militia(cubes_to_place));
-    return (resulting_loc, wealth);
+impl fmt::Display for TrainMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrainMode::Troops => write!(f, "Troops ({} Resources)", TRAIN_TROOPS_COST),
+            TrainMode::Prosperity => write!(f, "Prosperity ({} Resources)", TRAIN_PROSPERITY_COST),
+        }
+    }
 }
 
-// fn muster_strongholds(loc: Space) -> Space {}
+// Prosperity only rises as far as the Population and Towns allow
+fn prosperity_room(space: &Space) -> bool {
+    space.bottom_prosp < space.pop || space.top_prosp < space.strongholds_of(StrongholdClass::Town)
+}
+
+// Cavalry the Forts in a space can still take
+fn cavalry_room(space: &Space) -> u8 {
+    let capacity: u8 = space
+        .stronghold_sites
+        .iter()
+        .filter_map(|site| site.stronghold.as_ref())
+        .filter(|s| s.class == StrongholdClass::Fort)
+        .map(|s| s.capacity)
+        .sum();
+    return capacity.saturating_sub(space.units_of(Player::Dux, UnitClass::Cavalry));
+}
+
+// What the Dux can Train in a space for at most `budget` Resources. Troops
+// need a Fort with room; Prosperity needs a Fort or Briton control.
+pub fn train_modes(board: &Board, space: &Space, budget: u8) -> Vec<TrainMode> {
+    let fort: bool = space.strongholds_of(StrongholdClass::Fort) > 0;
+    let friendly: bool = matches!(space.control, Some(Player::Dux | Player::Civitates));
+    let mut modes: Vec<TrainMode> = vec![];
+    if cavalry_room(space) > 0 && board.available_units(Player::Dux, UnitClass::Cavalry) > 0 {
+        modes.push(TrainMode::Troops);
+    }
+    if (fort || friendly) && prosperity_room(space) {
+        modes.push(TrainMode::Prosperity);
+    }
+    modes.retain(|m| cost_in(board, Player::Dux, CommandType::Train, space.id, m.cost()) <= budget);
+    return modes;
+}
+
+// The least a space can cost to Train in, whichever way it is done
+fn cheapest_train(board: &Board, id: u8) -> u8 {
+    board
+        .space(id)
+        .and_then(|s| {
+            train_modes(board, s, u8::MAX)
+                .iter()
+                .map(|m| m.cost())
+                .min()
+        })
+        .unwrap_or(0)
+}
+
+// Dux Train in spaces of their choosing, paying Dux Resources for each
+// space as they go
+fn dux_train(
+    board: &mut Board,
+    decisions: &mut dyn DecisionProvider,
+    limited: bool,
+    free: bool,
+) -> Result<(), CommandError> {
+    let payment: Payment = Payment {
+        track: Track::DuxResources,
+        command: CommandType::Train,
+        base: cheapest_train,
+        free,
+    };
+    let candidates: Vec<u8> = board
+        .spaces()
+        .filter(|s| !train_modes(board, s, u8::MAX).is_empty())
+        .map(|s| s.id)
+        .collect();
+    let spaces: Vec<u8> = select_paid_spaces(
+        Player::Dux,
+        payment,
+        "Train in which space?",
+        board,
+        candidates,
+        limited,
+        decisions,
+    )?;
+    for (i, id) in spaces.iter().enumerate() {
+        // Enough is kept back for the cheapest way to Train in the spaces
+        // still to come
+        let reserve: u8 = payment.total(board, Player::Dux, &spaces[i + 1..]);
+        let budget: u8 = match free {
+            true => u8::MAX,
+            false => board.track(payment.track).saturating_sub(reserve),
+        };
+        train(board, *id, budget, free, decisions)?;
+    }
+    return Ok(());
+}
+
+// Trains one space for at most `budget` Resources, asking how when there is
+// more than one way. Nothing is paid if nothing comes of it, or if the
+// Train is free.
+pub fn train(
+    board: &mut Board,
+    id: u8,
+    budget: u8,
+    free: bool,
+    decisions: &mut dyn DecisionProvider,
+) -> Result<(), CommandError> {
+    let space: &Space = board.space(id).ok_or(BoardError::NoSuchSpace(id))?;
+    let modes: Vec<TrainMode> = train_modes(board, space, budget);
+    let mode: TrainMode = match modes.len() {
+        0 => {
+            narrate!("The Dux can no longer Train in {}", space.name);
+            return Ok(());
+        }
+        1 => modes[0],
+        _ => {
+            let options: Vec<String> = modes.iter().map(|m| m.to_string()).collect();
+            let prompt: String = format!("Train what in {}?", space.name);
+            modes[pick(Player::Dux, &prompt, &options, decisions)?]
+        }
+    };
+    let cost: u8 = cost_in(board, Player::Dux, CommandType::Train, id, mode.cost());
+    let trained: bool = match mode {
+        TrainMode::Troops => train_troops(board, id, decisions)? > 0,
+        TrainMode::Prosperity => {
+            board.shift_prosperity(id, 1)?;
+            let space: &Space = board.space(id).ok_or(BoardError::NoSuchSpace(id))?;
+            narrate!("Prosperity in {} rose by 1", space.name);
+            true
+        }
+    };
+    if trained && !free {
+        pay(board, Track::DuxResources, cost)?;
+    }
+    return Ok(());
+}
+
+// Cavalry from Available, as many as the Forts have room for. As the rules
+// outline for Train has it, the Dux may also place a Militia from the
+// Civitates' Available pieces for each Town and Hillfort in the space.
+// Says how many pieces were placed.
+fn train_troops(
+    board: &mut Board,
+    id: u8,
+    decisions: &mut dyn DecisionProvider,
+) -> Result<u8, CommandError> {
+    let space: &Space = board.space(id).ok_or(BoardError::NoSuchSpace(id))?;
+    let strongholds: u8 = space.strongholds_of(StrongholdClass::Town)
+        + space.strongholds_of(StrongholdClass::Hillfort);
+    let cavalry: u8 = count(
+        Player::Dux,
+        "Enter number of Cavalry to place",
+        cavalry_room(space).min(board.available_units(Player::Dux, UnitClass::Cavalry)),
+        decisions,
+    )?;
+    let militia: u8 = count(
+        Player::Dux,
+        "Enter number of Militia to place",
+        strongholds.min(board.available_units(Player::Civitates, UnitClass::Militia)),
+        decisions,
+    )?;
+    let placed: u8 = board.place_from_available(id, Player::Dux, UnitClass::Cavalry, cavalry)?
+        + board.place_from_available(id, Player::Civitates, UnitClass::Militia, militia)?;
+    if placed == 0 {
+        narrate!("Nothing was trained, so nothing is paid");
+    } else {
+        narrate!("Placed {} Cavalry and {} Militia", cavalry, militia);
+    }
+    return Ok(placed);
+}
 
 #[cfg(test)]
 mod tests {
-    use super::super::board::{Space, SpaceType, StrongholdSite, StrongholdSiteType, Terrain};
-    use super::super::concepts::{Player, Stronghold};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::super::board::{
+        CANTIACI, TEXTOVERDI, setup_barbarian_conspiracy, setup_etty_tyrants,
+    };
+    use super::super::concepts::Unit;
+    use super::super::decisions::{Bot, Scripted};
 
     use super::*;
 
     #[test]
-    fn test_muster() {
-        let town: Stronghold = Stronghold::new(StrongholdClass::Town, None, None);
-        let aquae_sulis: StrongholdSite<'_> = StrongholdSite {
-            name: String::from("Aquae Sulis"),
-            site_type: StrongholdSiteType::Town,
-            stronghold: Some(&town),
-        };
-        let south_cadbury: StrongholdSite<'_> = StrongholdSite {
-            name: String::from("South Cadbury"),
-            site_type: StrongholdSiteType::Hillfort,
-            stronghold: None,
+    fn test_muster_units_swaps_in_comitates() {
+        let mut board: Board = setup_etty_tyrants();
+        let comitates: u8 = board.available_units(Player::Civitates, UnitClass::Comitates);
+        let id: u8 = board
+            .spaces()
+            .find(|s| muster_units_in(s) >= 2)
+            .map(|s| s.id)
+            .unwrap();
+        let units: u8 = muster_units_in(board.space(id).unwrap());
+        let wealth: u8 = board.track(Track::Wealth);
+        let before: &Space = board.space(id).unwrap();
+        let militia: u8 = before.units_of(Player::Civitates, UnitClass::Militia);
+        let on_map: u8 = before.units_of(Player::Civitates, UnitClass::Comitates);
+        muster_units(&mut board, id, &mut Scripted::new(vec![1])).unwrap();
+        let space: &Space = board.space(id).unwrap();
+        assert_eq!(
+            space.units_of(Player::Civitates, UnitClass::Comitates),
+            on_map + 1
+        );
+        assert_eq!(
+            space.units_of(Player::Civitates, UnitClass::Militia),
+            militia + units - 1
+        );
+        assert_eq!(board.track(Track::Wealth), wealth - 1);
+        assert_eq!(
+            board.available_units(Player::Civitates, UnitClass::Comitates),
+            comitates - 1
+        );
+
+        // No more Comitates than there are units to place
+        assert!(matches!(
+            muster_units(&mut board, id, &mut Scripted::new(vec![99])),
+            Err(CommandError::AnswerOutOfRange { answer: 99, .. })
+        ));
+    }
+
+    #[test]
+    fn test_muster_places_strongholds_on_matching_sites() {
+        let mut board: Board = setup_etty_tyrants();
+        let space: &Space = board
+            .spaces()
+            .find(|s| {
+                s.control == Some(Player::Civitates)
+                    && s.empty_site_for(StrongholdClass::Hillfort).is_some()
+            })
+            .unwrap();
+        let id: u8 = space.id;
+        let hillforts: u8 = space.strongholds_of(StrongholdClass::Hillfort);
+        let modes: Vec<MusterMode> = muster_modes(&board, space);
+        let answer: usize = modes
+            .iter()
+            .position(|m| *m == MusterMode::Stronghold(StrongholdClass::Hillfort))
+            .unwrap();
+        muster(&mut board, id, &mut Scripted::new(vec![answer])).unwrap();
+        assert_eq!(
+            board
+                .space(id)
+                .unwrap()
+                .strongholds_of(StrongholdClass::Hillfort),
+            hillforts + 1
+        );
+    }
+
+    #[test]
+    fn test_muster_costs_resources() {
+        let mut board: Board = setup_etty_tyrants();
+        let resources: u8 = board.track(Track::BritonResources);
+        board.shift_track(Track::BritonResources, -(resources as i8));
+        // With nothing to pay with there is nowhere to Muster
+        execute(
+            Player::Civitates,
+            CommandType::Muster,
+            &mut board,
+            &mut Dice::new(&mut ChaCha8Rng::seed_from_u64(0)),
+            &mut Scripted::new(vec![]),
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            pay(&mut board, Track::BritonResources, 1),
+            Err(CommandError::CannotAfford {
+                cost: 1,
+                resources: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_muster_units_stop_at_the_militia_available() {
+        let mut board: Board = setup_etty_tyrants();
+        let mut ids: Vec<u8> = board
+            .spaces()
+            .filter(|s| muster_units_in(s) >= 2)
+            .map(|s| s.id)
+            .collect();
+        ids.sort();
+        let (id, elsewhere): (u8, u8) = (ids[0], ids[1]);
+        // One Militia left, and no Wealth to swap in Comitates
+        let militia: u8 = board.available_units(Player::Civitates, UnitClass::Militia);
+        board
+            .place_from_available(
+                elsewhere,
+                Player::Civitates,
+                UnitClass::Militia,
+                militia - 1,
+            )
+            .unwrap();
+        let wealth: u8 = board.track(Track::Wealth);
+        board.shift_track(Track::Wealth, -(wealth as i8));
+        let before: u8 = board
+            .space(id)
+            .unwrap()
+            .units_of(Player::Civitates, UnitClass::Militia);
+        let placed: u8 = muster_units(&mut board, id, &mut Scripted::new(vec![0])).unwrap();
+        assert_eq!(placed, 1);
+        assert_eq!(
+            board
+                .space(id)
+                .unwrap()
+                .units_of(Player::Civitates, UnitClass::Militia),
+            before + 1
+        );
+        assert_eq!(
+            board.available_units(Player::Civitates, UnitClass::Militia),
+            0
+        );
+    }
+
+    #[test]
+    fn test_muster_pays_only_where_something_is_placed() {
+        let mut board: Board = setup_etty_tyrants();
+        let mut ids: Vec<u8> = board
+            .spaces()
+            .filter(|s| muster_modes(&board, s) == vec![MusterMode::Units])
+            .map(|s| s.id)
+            .collect();
+        ids.sort();
+        // A single Militia to Muster, and no Wealth for Comitates
+        let militia: u8 = board.available_units(Player::Civitates, UnitClass::Militia);
+        board
+            .place_from_available(ids[2], Player::Civitates, UnitClass::Militia, militia - 1)
+            .unwrap();
+        let wealth: u8 = board.track(Track::Wealth);
+        board.shift_track(Track::Wealth, -(wealth as i8));
+        let resources: u8 = board.track(Track::BritonResources);
+        let mut candidates: Vec<u8> = board
+            .spaces()
+            .filter(|s| !muster_modes(&board, s).is_empty())
+            .map(|s| s.id)
+            .collect();
+        candidates.sort();
+        let first: usize = candidates.iter().position(|id| *id == ids[0]).unwrap();
+        candidates.remove(first);
+        let second: usize = candidates.iter().position(|id| *id == ids[1]).unwrap();
+        let done: usize = candidates.len() - 1;
+        // The first space takes the last Militia, leaving the second empty
+        execute(
+            Player::Civitates,
+            CommandType::Muster,
+            &mut board,
+            &mut Dice::new(&mut ChaCha8Rng::seed_from_u64(0)),
+            &mut Scripted::new(vec![first, second, done, 0, 0]),
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(board.track(Track::BritonResources), resources - MUSTER_COST);
+    }
+
+    #[test]
+    fn test_limited_command_takes_one_space() {
+        let board: Board = setup_etty_tyrants();
+        let mut candidates: Vec<u8> = board.spaces().map(|s| s.id).take(3).collect();
+        candidates.sort();
+        let mut decisions: Scripted = Scripted::new(vec![2]);
+        let chosen: Vec<u8> = select_spaces(
+            Player::Dux,
+            "Where?",
+            &board,
+            candidates.clone(),
+            true,
+            &mut decisions,
+        )
+        .unwrap();
+        assert_eq!(chosen, vec![candidates[2]]);
+        assert_eq!(decisions.remaining(), 0);
+
+        // Otherwise the player says when they are done
+        let mut decisions: Scripted = Scripted::new(vec![0, 0, 1]);
+        let chosen: Vec<u8> = select_spaces(
+            Player::Dux,
+            "Where?",
+            &board,
+            candidates.clone(),
+            false,
+            &mut decisions,
+        )
+        .unwrap();
+        assert_eq!(chosen, vec![candidates[0], candidates[1]]);
+    }
+
+    #[test]
+    fn test_limited_command_has_no_feat() {
+        let mut board: Board = setup_etty_tyrants();
+        let e: CommandError = feat(
+            Player::Saxons,
+            FeatType::Ravage,
+            &mut board,
+            &mut Scripted::new(vec![]),
+            true,
+        )
+        .unwrap_err();
+        assert_eq!(e, CommandError::FeatWithLimited(FeatType::Ravage));
+        assert!(matches!(
+            feat(
+                Player::Saxons,
+                FeatType::Build,
+                &mut board,
+                &mut Scripted::new(vec![]),
+                false,
+            ),
+            Err(CommandError::NotFactionFeat { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unwritten_commands_and_feats_are_refused() {
+        let mut board: Board = setup_etty_tyrants();
+        let before: Board = board.clone();
+        let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(0);
+        for player in [
+            Player::Civitates,
+            Player::Dux,
+            Player::Saxons,
+            Player::Scotti,
+        ] {
+            for command in CommandType::of(player) {
+                if CommandType::implemented(player).contains(command) {
+                    continue;
+                }
+                assert_eq!(
+                    execute(
+                        player,
+                        *command,
+                        &mut board,
+                        &mut Dice::new(&mut rng),
+                        &mut Scripted::new(vec![]),
+                        false,
+                        false,
+                    ),
+                    Err(CommandError::CommandNotImplemented {
+                        player,
+                        command: *command
+                    })
+                );
+            }
+            assert!(FeatType::implemented(player).is_empty());
+            let first: FeatType = FeatType::of(player)[0];
+            assert_eq!(
+                feat(player, first, &mut board, &mut Scripted::new(vec![]), false),
+                Err(CommandError::FeatNotImplemented {
+                    player,
+                    feat: first
+                })
+            );
+        }
+        assert_eq!(
+            CommandType::implemented(Player::Dux),
+            vec![CommandType::Train]
+        );
+        assert!(CommandType::implemented(Player::Saxons).is_empty());
+        assert_eq!(board.track(Track::Wealth), before.track(Track::Wealth));
+    }
+
+    #[test]
+    fn test_limited_origins_are_next_to_the_one_destination() {
+        let board: Board = setup_etty_tyrants();
+        let destination: &Space = board.spaces().find(|s| !s.adj_spaces.is_empty()).unwrap();
+        let found: Vec<u8> = origins(&board, &[destination.id], true).unwrap();
+        assert!(found.iter().all(|id| destination.adj_spaces.contains(id)));
+        assert_eq!(
+            origins(&board, &[destination.id, destination.adj_spaces[0]], true),
+            Err(CommandError::TooManySpaces { chosen: 2 })
+        );
+    }
+
+    #[test]
+    fn test_paid_spaces_stop_at_what_can_be_paid() {
+        let mut board: Board = setup_etty_tyrants();
+        let resources: u8 = board.track(Track::BritonResources);
+        board.shift_track(Track::BritonResources, 2 - resources as i8);
+        let payment: Payment = Payment {
+            track: Track::BritonResources,
+            command: CommandType::Muster,
+            base: |_, _| 1,
+            free: false,
         };
+        let candidates: Vec<u8> = board.spaces().map(|s| s.id).collect();
+        // Once two spaces are chosen there is nothing left to offer, not
+        // even Done
+        let mut decisions: Scripted = Scripted::new(vec![0, 0]);
+        let chosen: Vec<u8> = select_paid_spaces(
+            Player::Civitates,
+            payment,
+            "Where?",
+            &board,
+            candidates,
+            false,
+            &mut decisions,
+        )
+        .unwrap();
+        assert_eq!(chosen.len(), 2);
+        assert_eq!(payment.total(&board, Player::Civitates, &chosen), 2);
+    }
+
+    #[test]
+    fn test_limited_march_goes_to_one_space() {
+        let mut board: Board = setup_etty_tyrants();
+        let origin: &Space = board
+            .spaces()
+            .find(|s| s.units_of(Player::Civitates, UnitClass::Militia) > 1)
+            .unwrap();
+        let (origin, to): (u8, u8) = (origin.id, origin.adj_spaces[0]);
+        let militia: u8 = board
+            .space(to)
+            .unwrap()
+            .units_of(Player::Civitates, UnitClass::Militia);
+        let comitates: u8 = board
+            .space(origin)
+            .unwrap()
+            .units_of(Player::Civitates, UnitClass::Comitates);
+        let mut answers: Vec<usize> = vec![2];
+        if comitates > 0 {
+            answers.insert(0, 0);
+        }
+        march_from(
+            &mut board,
+            origin,
+            Some(to),
+            &mut BTreeMap::new(),
+            &mut Scripted::new(answers),
+        )
+        .unwrap();
+        assert_eq!(
+            board
+                .space(to)
+                .unwrap()
+                .units_of(Player::Civitates, UnitClass::Militia),
+            militia + 2
+        );
 
-        let avail: CivitatesHolding = CivitatesHolding::blank();
-
-        let test_space: Space<'_> = Space {
-            id: 0,
-            name: String::from("Durotriges"),
-            space_type: SpaceType::Region,
-            terrain: Some(Terrain::Clear),
-            adj_spaces: vec![],
-            adj_seas: vec![],
-            pop: 2,
-            max_pop: 3,
-            top_prosp: 2,
-            bottom_prosp: 2,
-            stronghold_sites: vec![aquae_sulis, south_cadbury],
-            units: vec![],
-            control: Some(Player::Civitates),
+        // However the bot plays it, everything ends up in one space
+        for seed in 0..10 {
+            let mut board: Board = setup_etty_tyrants();
+            let before: Board = board.clone();
+            execute(
+                Player::Civitates,
+                CommandType::March,
+                &mut board,
+                &mut Dice::new(&mut ChaCha8Rng::seed_from_u64(seed)),
+                &mut Bot::new(seed),
+                true,
+                false,
+            )
+            .unwrap();
+            let gained: usize = board
+                .spaces()
+                .filter(|s| marching_units(s) > marching_units(before.space(s.id).unwrap()))
+                .count();
+            assert!(gained <= 1);
+        }
+    }
+
+    #[test]
+    fn test_units_march_only_once() {
+        let mut board: Board = setup_etty_tyrants();
+        let no_comitates = |s: &Space| s.units_of(Player::Civitates, UnitClass::Comitates) == 0;
+        let mut ids: Vec<u8> = board
+            .spaces()
+            .filter(|s| no_comitates(s))
+            .map(|s| s.id)
+            .collect();
+        ids.sort();
+        let (a, b): (u8, u8) = ids
+            .iter()
+            .find_map(|a| {
+                let space: &Space = board.space(*a).unwrap();
+                let b: &u8 = space
+                    .adj_spaces
+                    .iter()
+                    .find(|b| board.space(**b).is_some_and(no_comitates))?;
+                Some((*a, *b))
+            })
+            .unwrap();
+        board
+            .place_from_available(a, Player::Civitates, UnitClass::Militia, 2)
+            .unwrap();
+        board
+            .place_from_available(b, Player::Civitates, UnitClass::Militia, 2)
+            .unwrap();
+        let militia = |board: &Board, id: u8| {
+            board
+                .space(id)
+                .unwrap()
+                .units_of(Player::Civitates, UnitClass::Militia)
         };
-        let (after, _): (Space<'_>, u8) = muster(test_space, 2, avail);
-        assert_eq!(after.units.len(), 3);
+        let (from_a, from_b): (u8, u8) = (militia(&board, a), militia(&board, b));
+        let mut arrived: BTreeMap<(u8, UnitClass), u8> = BTreeMap::new();
+        march_from(
+            &mut board,
+            a,
+            Some(b),
+            &mut arrived,
+            &mut Scripted::new(vec![from_a as usize]),
+        )
+        .unwrap();
+        // What came from the first origin cannot march on from the second
+        assert_eq!(
+            march_from(
+                &mut board,
+                b,
+                Some(a),
+                &mut arrived,
+                &mut Scripted::new(vec![from_b as usize + 1]),
+            ),
+            Err(CommandError::AnswerOutOfRange {
+                answer: from_b as usize + 1,
+                answers: from_b as usize + 1
+            })
+        );
+        march_from(
+            &mut board,
+            b,
+            Some(a),
+            &mut arrived,
+            &mut Scripted::new(vec![from_b as usize]),
+        )
+        .unwrap();
+        assert_eq!(militia(&board, a), from_b);
+        assert_eq!(militia(&board, b), from_a);
+    }
+
+    #[test]
+    fn test_trade_needs_control_and_a_town() {
+        let board: Board = setup_etty_tyrants();
+        let space: &Space = board.spaces().find(|s| can_trade(s)).unwrap();
+
+        let mut lost: Space = space.clone();
+        lost.control = Some(Player::Saxons);
+        assert!(!can_trade(&lost));
+        lost.control = None;
+        assert!(!can_trade(&lost));
+
+        let mut no_town: Space = space.clone();
+        for site in no_town.stronghold_sites.iter_mut() {
+            if site
+                .stronghold
+                .as_ref()
+                .is_some_and(|s| s.class == StrongholdClass::Town)
+            {
+                site.stronghold = None;
+            }
+        }
+        assert_eq!(no_town.control, Some(Player::Civitates));
+        assert!(!can_trade(&no_town));
+    }
+
+    #[test]
+    fn test_trade_income_and_preview() {
+        let mut board: Board = setup_etty_tyrants();
+        let space: &Space = board.spaces().find(|s| can_trade(s)).unwrap();
+        let id: u8 = space.id;
+        let income: Income = trade_income(space);
+        assert_eq!(income.wealth, space.top_prosp);
+        assert_eq!(income.resources, space.bottom_prosp);
+
+        // Choosing again throws the first choice away
+        let mut candidates: Vec<u8> = board
+            .spaces()
+            .filter(|s| can_trade(s))
+            .map(|s| s.id)
+            .collect();
+        candidates.sort();
+        let index: usize = candidates.iter().position(|c| *c == id).unwrap();
+        let wealth: u8 = board.track(Track::Wealth);
+        let resources: u8 = board.track(Track::BritonResources);
+        let mut decisions: Scripted = Scripted::new(vec![index, 1, index, 0]);
+        civitates_trade(&mut board, &mut decisions, true).unwrap();
+        assert_eq!(decisions.remaining(), 0);
+        assert_eq!(board.track(Track::Wealth), wealth + income.wealth);
+        assert_eq!(
+            board.track(Track::BritonResources),
+            resources + income.resources
+        );
+    }
+
+    #[test]
+    fn test_civitates_battle_rolls_for_both_sides() {
+        let mut board: Board = setup_etty_tyrants();
+        let space: u8 = board
+            .spaces()
+            .find(|s| {
+                s.units.is_empty()
+                    && s.stronghold_sites
+                        .iter()
+                        .all(|site| site.stronghold.is_none())
+            })
+            .map(|s| s.id)
+            .unwrap();
+        board.place_units(space, Unit::con_comitates(3)).unwrap();
+        board
+            .place_units(space, Unit::con_raiders(Player::Saxons, 2))
+            .unwrap();
+        let mut candidates: Vec<u8> = board
+            .spaces()
+            .filter(|s| marching_units(s) > 0 && !enemies_in(s).is_empty())
+            .map(|s| s.id)
+            .collect();
+        candidates.sort();
+        let index: usize = candidates.iter().position(|c| *c == space).unwrap();
+        let resources: u8 = board.track(Track::BritonResources);
+        let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(0);
+        let mut dice: Dice = Dice::new(&mut rng);
+        execute(
+            Player::Civitates,
+            CommandType::Battle,
+            &mut board,
+            &mut dice,
+            &mut Scripted::new(vec![index]),
+            true,
+            false,
+        )
+        .unwrap();
+        assert_eq!(dice.rolled.len(), 5);
+        assert_eq!(board.track(Track::BritonResources), resources - BATTLE_COST);
+    }
+
+    #[test]
+    fn test_train_cavalry_at_a_fort() {
+        let mut board: Board = setup_barbarian_conspiracy();
+        let resources: u8 = board.track(Track::DuxResources);
+        let available: u8 = board.available_units(Player::Dux, UnitClass::Cavalry);
+        let militia: u8 = board.available_units(Player::Civitates, UnitClass::Militia);
+        // Cantiaci's Fort holds 2 Cavalry and has 1 already, and its Town
+        // takes 1 Militia. Its Prosperity is full, so Troops it is.
+        assert_eq!(
+            train_modes(&board, board.space(CANTIACI).unwrap(), resources),
+            vec![TrainMode::Troops]
+        );
+        assert_eq!(
+            train(
+                &mut board,
+                CANTIACI,
+                resources,
+                false,
+                &mut Scripted::new(vec![2])
+            ),
+            Err(CommandError::AnswerOutOfRange {
+                answer: 2,
+                answers: 2
+            })
+        );
+        train(
+            &mut board,
+            CANTIACI,
+            resources,
+            false,
+            &mut Scripted::new(vec![1, 1]),
+        )
+        .unwrap();
+        let space: &Space = board.space(CANTIACI).unwrap();
+        assert_eq!(space.units_of(Player::Dux, UnitClass::Cavalry), 2);
+        assert_eq!(space.units_of(Player::Civitates, UnitClass::Militia), 2);
+        assert_eq!(
+            board.track(Track::DuxResources),
+            resources - TRAIN_TROOPS_COST
+        );
+        assert_eq!(
+            board.available_units(Player::Dux, UnitClass::Cavalry),
+            available - 1
+        );
+        assert_eq!(
+            board.available_units(Player::Civitates, UnitClass::Militia),
+            militia - 1
+        );
+        // The Fort is full now
+        assert!(train_modes(&board, board.space(CANTIACI).unwrap(), resources).is_empty());
+    }
+
+    #[test]
+    fn test_train_nothing_costs_nothing() {
+        let mut board: Board = setup_barbarian_conspiracy();
+        let resources: u8 = board.track(Track::DuxResources);
+        train(
+            &mut board,
+            CANTIACI,
+            resources,
+            false,
+            &mut Scripted::new(vec![0, 0]),
+        )
+        .unwrap();
+        let space: &Space = board.space(CANTIACI).unwrap();
+        assert_eq!(space.units_of(Player::Dux, UnitClass::Cavalry), 1);
+        assert_eq!(space.units_of(Player::Civitates, UnitClass::Militia), 1);
+        assert_eq!(board.track(Track::DuxResources), resources);
+    }
+
+    #[test]
+    fn test_train_prosperity_within_limits() {
+        let mut board: Board = setup_barbarian_conspiracy();
+        let resources: u8 = board.track(Track::DuxResources);
+        // Textoverdi's Fort is full, but the Dux hold the space and its
+        // Prosperity has room
+        let space: &Space = board.space(TEXTOVERDI).unwrap();
+        assert_eq!(
+            train_modes(&board, space, resources),
+            vec![TrainMode::Prosperity]
+        );
+        let prosperity: u8 = space.top_prosp + space.bottom_prosp;
+        train(
+            &mut board,
+            TEXTOVERDI,
+            resources,
+            false,
+            &mut Scripted::new(vec![]),
+        )
+        .unwrap();
+        let space: &Space = board.space(TEXTOVERDI).unwrap();
+        assert_eq!(space.top_prosp + space.bottom_prosp, prosperity + 1);
+        assert_eq!(
+            board.track(Track::DuxResources),
+            resources - TRAIN_PROSPERITY_COST
+        );
+
+        // A full space has no room left
+        while prosperity_room(board.space(TEXTOVERDI).unwrap()) {
+            board.shift_prosperity(TEXTOVERDI, 1).unwrap();
+        }
+        assert!(train_modes(&board, board.space(TEXTOVERDI).unwrap(), resources).is_empty());
+    }
+
+    #[test]
+    fn test_limited_train_by_bot() {
+        for seed in 0..10 {
+            let mut board: Board = setup_barbarian_conspiracy();
+            let before: Board = board.clone();
+            execute(
+                Player::Dux,
+                CommandType::Train,
+                &mut board,
+                &mut Dice::new(&mut ChaCha8Rng::seed_from_u64(seed)),
+                &mut Bot::new(seed),
+                true,
+                false,
+            )
+            .unwrap();
+            let mut ids: Vec<u8> = board.spaces().map(|s| s.id).collect();
+            ids.sort();
+            let changed: Vec<u8> = ids
+                .into_iter()
+                .filter(|id| {
+                    let (now, was): (&Space, &Space) =
+                        (board.space(*id).unwrap(), before.space(*id).unwrap());
+                    now.units.len() != was.units.len()
+                        || now.top_prosp + now.bottom_prosp != was.top_prosp + was.bottom_prosp
+                })
+                .collect();
+            assert!(changed.len() <= 1);
+            let spent: u8 = before.track(Track::DuxResources) - board.track(Track::DuxResources);
+            let Some(id) = changed.first() else {
+                assert_eq!(spent, 0);
+                continue;
+            };
+            let (now, was): (&Space, &Space) =
+                (board.space(*id).unwrap(), before.space(*id).unwrap());
+            let gained = |player: Player, class: UnitClass| {
+                now.units_of(player, class) - was.units_of(player, class)
+            };
+            let lost = |player: Player, class: UnitClass| {
+                before.available_units(player, class) - board.available_units(player, class)
+            };
+            if now.units.len() == was.units.len() {
+                assert_eq!(
+                    now.top_prosp + now.bottom_prosp,
+                    was.top_prosp + was.bottom_prosp + 1
+                );
+                assert_eq!(spent, TRAIN_PROSPERITY_COST);
+            } else {
+                assert_eq!(now.top_prosp, was.top_prosp);
+                assert_eq!(now.bottom_prosp, was.bottom_prosp);
+                assert_eq!(
+                    gained(Player::Dux, UnitClass::Cavalry),
+                    lost(Player::Dux, UnitClass::Cavalry)
+                );
+                assert_eq!(
+                    gained(Player::Civitates, UnitClass::Militia),
+                    lost(Player::Civitates, UnitClass::Militia)
+                );
+                assert_eq!(spent, TRAIN_TROOPS_COST);
+            }
+        }
     }
 }
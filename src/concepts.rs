@@ -1,12 +1,14 @@
 use colored::Colorize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 
 use crate::concepts::{
     Nationality::Briton,
-    Player::Civitates,
-    UnitClass::{Comitates, Militia},
+    Player::{Civitates, Dux},
+    UnitClass::{Cavalry, Comitates, Militia, Raider, Warband},
 };
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Player {
     Civitates,
     Dux,
@@ -14,6 +16,33 @@ pub enum Player {
     Scotti,
 }
 
+impl Player {
+    // In the order the factions are listed on the track
+    pub const ALL: [Player; 4] = [
+        Player::Civitates,
+        Player::Dux,
+        Player::Saxons,
+        Player::Scotti,
+    ];
+}
+
+impl FromStr for Player {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "civitates" => Ok(Player::Civitates),
+            "dux" => Ok(Player::Dux),
+            "saxons" => Ok(Player::Saxons),
+            "scotti" => Ok(Player::Scotti),
+            _ => Err(format!(
+                "unknown faction {}, expected civitates, dux, saxons or scotti",
+                s
+            )),
+        }
+    }
+}
+
 impl fmt::Display for Player {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -36,23 +65,25 @@ impl fmt::Debug for Player {
     }
 }
 
-// Board
-
-
-struct PieceCount {
-    units: Vec<Unit>,
-    strongholds: Vec<Stronghold>,
-}
-
 // Components
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Nationality {
     Briton,
     Saxon,
     Scotti,
 }
 
-#[derive(Clone, Debug)]
+impl Nationality {
+    pub fn of(player: Player) -> Nationality {
+        match player {
+            Player::Civitates | Player::Dux => Nationality::Briton,
+            Player::Saxons => Nationality::Saxon,
+            Player::Scotti => Nationality::Scotti,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Stronghold {
     pub controller: Player,
     pub class: StrongholdClass,
@@ -105,7 +136,7 @@ impl Stronghold {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum StrongholdClass {
     Fort,
     Hillfort,
@@ -113,7 +144,7 @@ pub enum StrongholdClass {
     Settlement,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Unit {
     pub designation: UnitClass,
     pub controller: Player,
@@ -121,7 +152,7 @@ pub struct Unit {
     pub plunder: bool,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum UnitClass {
     Cavalry,
     Comitates,
@@ -159,24 +190,32 @@ impl Unit {
         }
         return ret;
     }
-}
 
-pub struct CivitatesHolding {
-    out_of_play_comitates: u8,
-    available_comitates: u8,
-    available_militia: u8,
-    available_hillforts: u8,
-    available_towns: u8,
-}
+    pub fn con_cavalry(amt: u8) -> Vec<Unit> {
+        Unit::con(Cavalry, Dux, Briton, amt)
+    }
 
-impl CivitatesHolding {
-    pub fn blank() -> CivitatesHolding {
-        CivitatesHolding {
-            out_of_play_comitates: 15,
-            available_comitates: 0,
-            available_militia: 30,
-            available_hillforts: 15,
-            available_towns: 15,
-        }
+    // Raiders and Warbands belong to one of the barbarian factions
+    pub fn con_raiders(player: Player, amt: u8) -> Vec<Unit> {
+        Unit::con(Raider, player, Nationality::of(player), amt)
+    }
+
+    pub fn con_warbands(player: Player, amt: u8) -> Vec<Unit> {
+        Unit::con(Warband, player, Nationality::of(player), amt)
+    }
+
+    fn con(
+        designation: UnitClass,
+        controller: Player,
+        nationality: Nationality,
+        amt: u8,
+    ) -> Vec<Unit> {
+        let unit: Unit = Unit {
+            designation,
+            controller,
+            nationality,
+            plunder: false,
+        };
+        return vec![unit; amt as usize];
     }
 }
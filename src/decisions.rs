@@ -0,0 +1,392 @@
+use dialoguer::{Input, Select};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+#[cfg(test)]
+use std::collections::VecDeque;
+
+use super::concepts::Player;
+use super::effects::Side;
+use super::events::Event;
+use super::inspect::{self, DeckView};
+use super::sequence_of_play::Action;
+
+// Every question the game can put to a faction. Answers are an index into
+// the options, or the number itself for a count.
+#[derive(Clone, Debug)]
+pub enum Decision<'a> {
+    Action {
+        options: &'a [Action],
+    },
+    Side {
+        event: &'a Event,
+    },
+    // 0 declines, 1 plays the Pivotal event
+    Pivotal {
+        event: &'a Event,
+    },
+    // Spaces, factions and anything else picked from a list
+    Choice {
+        prompt: &'a str,
+        options: &'a [String],
+    },
+    // Anywhere from 0 to max inclusive
+    Count {
+        prompt: &'a str,
+        max: u8,
+    },
+}
+
+pub const SIDES: [Side; 2] = [Side::Unshaded, Side::Shaded];
+
+impl Decision<'_> {
+    // How many different answers there are
+    pub fn answers(&self) -> usize {
+        match self {
+            Decision::Action { options } => options.len(),
+            Decision::Side { .. } | Decision::Pivotal { .. } => 2,
+            Decision::Choice { options, .. } => options.len(),
+            Decision::Count { max, .. } => *max as usize + 1,
+        }
+    }
+}
+
+// Instead of answering, a player can ask to step back or forward through
+// the game's history, to save it or to look through its journal. The step
+// being asked about is abandoned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interrupt {
+    Undo,
+    Redo,
+    Save,
+    Journal,
+    // Whoever was answering cannot go on, so the game stops
+    Stop,
+}
+
+pub trait DecisionProvider {
+    // The view is there for anyone who wants to look at the cards first;
+    // it is None outside of the sequence of play
+    fn decide(
+        &mut self,
+        player: Player,
+        decision: &Decision,
+        view: Option<&DeckView>,
+    ) -> Result<usize, Interrupt>;
+}
+
+// Asks whoever is at the keyboard
+pub struct Terminal;
+
+const UNDO: &str = "Undo";
+const REDO: &str = "Redo";
+const SAVE: &str = "Save game";
+const JOURNAL: &str = "Journal";
+
+// Every list also offers undo, redo, saving and the journal
+fn select(prompt: &str, items: &[String], view: Option<&DeckView>) -> Result<usize, Interrupt> {
+    let mut options: Vec<String> = items.to_vec();
+    options.push(String::from(UNDO));
+    options.push(String::from(REDO));
+    options.push(String::from(SAVE));
+    options.push(String::from(JOURNAL));
+    let picked: usize = match view {
+        Some(view) => inspect::select(prompt, &options, view),
+        None => Select::new().with_prompt(prompt).items(&options).interact(),
+    }
+    // Without a terminal there is no one to ask
+    .map_err(|_| Interrupt::Stop)?;
+    if picked < items.len() {
+        return Ok(picked);
+    }
+    match options[picked].as_str() {
+        UNDO => return Err(Interrupt::Undo),
+        REDO => return Err(Interrupt::Redo),
+        SAVE => return Err(Interrupt::Save),
+        _ => return Err(Interrupt::Journal),
+    }
+}
+
+impl DecisionProvider for Terminal {
+    fn decide(
+        &mut self,
+        player: Player,
+        decision: &Decision,
+        view: Option<&DeckView>,
+    ) -> Result<usize, Interrupt> {
+        match decision {
+            Decision::Action { options } => {
+                println!("\nGetting action from {}", player);
+                let labels: Vec<String> = options.iter().map(|a| a.to_string()).collect();
+                select("Select one of the following actions!", &labels, view)
+            }
+            Decision::Side { event } => {
+                println!(
+                    "{}\n  Unshaded: {}\n  Shaded: {}",
+                    event.name, event.unshaded, event.shaded
+                );
+                let labels: Vec<String> = SIDES.iter().map(|s| s.to_string()).collect();
+                select("Play which side of the event?", &labels, view)
+            }
+            Decision::Pivotal { event } => {
+                println!("{} may play {}: {}", player, event.name, event.unshaded);
+                let labels: [String; 2] = [String::from("No"), String::from("Yes")];
+                select(
+                    &format!("Does {} play {}?", player, event.name),
+                    &labels,
+                    view,
+                )
+            }
+            Decision::Choice { prompt, options } => select(prompt, options, view),
+            Decision::Count { prompt, max } => loop {
+                let keywords: &str = match view {
+                    Some(_) => "cards/undo/redo/save/journal",
+                    None => "undo/redo/save/journal",
+                };
+                let entered: String = Input::new()
+                    .allow_empty(true)
+                    .with_prompt(format!(
+                        "{} (0 to {}, default: 0, or {})",
+                        prompt, max, keywords
+                    ))
+                    .interact()
+                    .map_err(|_| Interrupt::Stop)?;
+                if let Some(view) = view
+                    && entered.trim() == "cards"
+                {
+                    view.browse().map_err(|_| Interrupt::Stop)?;
+                    continue;
+                }
+                match entered.trim() {
+                    "" => return Ok(0),
+                    "undo" => return Err(Interrupt::Undo),
+                    "redo" => return Err(Interrupt::Redo),
+                    "save" => return Err(Interrupt::Save),
+                    "journal" => return Err(Interrupt::Journal),
+                    _ => {}
+                }
+                match entered.trim().parse::<u8>() {
+                    Ok(n) if n <= *max => return Ok(n as usize),
+                    Ok(n) => println!("Error: {} is more than the {} allowed", n, max),
+                    _ => println!("Invalid input, must enter a non-negative integer"),
+                }
+            },
+        }
+    }
+}
+
+// Answers from a list made up in advance, for tests
+#[cfg(test)]
+pub struct Scripted {
+    answers: VecDeque<usize>,
+}
+
+#[cfg(test)]
+impl Scripted {
+    pub fn new(answers: Vec<usize>) -> Self {
+        Scripted {
+            answers: VecDeque::from(answers),
+        }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.answers.len()
+    }
+}
+
+#[cfg(test)]
+impl DecisionProvider for Scripted {
+    // Answers are passed on as written, even out of range ones, so tests can
+    // check how bad input is handled
+    fn decide(
+        &mut self,
+        player: Player,
+        decision: &Decision,
+        _: Option<&DeckView>,
+    ) -> Result<usize, Interrupt> {
+        Ok(self.answers.pop_front().unwrap_or_else(|| {
+            panic!(
+                "Ran out of scripted answers for {} at {:?}",
+                player, decision
+            )
+        }))
+    }
+}
+
+// Plays any legal option at random, preferring to act rather than pass and
+// always taking a Pivotal event when offered. Seeded, so a game between bots
+// can be played again.
+pub struct Bot {
+    rng: ChaCha8Rng,
+}
+
+impl Bot {
+    pub fn new(seed: u64) -> Self {
+        Bot {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl DecisionProvider for Bot {
+    fn decide(
+        &mut self,
+        _: Player,
+        decision: &Decision,
+        _: Option<&DeckView>,
+    ) -> Result<usize, Interrupt> {
+        let answer: usize = match decision {
+            Decision::Pivotal { .. } => 1,
+            Decision::Action { options } => {
+                let acting: Vec<usize> = (0..options.len())
+                    .filter(|i| options[*i] != Action::Pass)
+                    .collect();
+                if acting.is_empty() {
+                    0
+                } else {
+                    acting[self.rng.random_range(0..acting.len())]
+                }
+            }
+            _ => self.rng.random_range(0..decision.answers()),
+        };
+        return Ok(answer);
+    }
+}
+
+// Some factions played at the keyboard and the rest by bots
+pub struct Seats {
+    humans: Vec<Player>,
+    terminal: Terminal,
+    bot: Bot,
+}
+
+impl Seats {
+    pub fn new(humans: Vec<Player>, bot_seed: u64) -> Self {
+        Seats {
+            humans,
+            terminal: Terminal,
+            bot: Bot::new(bot_seed),
+        }
+    }
+
+    pub fn is_human(&self, player: Player) -> bool {
+        self.humans.contains(&player)
+    }
+}
+
+impl DecisionProvider for Seats {
+    fn decide(
+        &mut self,
+        player: Player,
+        decision: &Decision,
+        view: Option<&DeckView>,
+    ) -> Result<usize, Interrupt> {
+        if self.is_human(player) {
+            return self.terminal.decide(player, decision, view);
+        }
+        self.bot.decide(player, decision, view)
+    }
+}
+
+// Hands the cards on show to everything `decisions` is asked, for commands
+// and feats that do not pass them along themselves
+pub struct ShowingCards<'a, 'v> {
+    pub decisions: &'a mut dyn DecisionProvider,
+    pub view: &'a DeckView<'v>,
+}
+
+impl DecisionProvider for ShowingCards<'_, '_> {
+    fn decide(
+        &mut self,
+        player: Player,
+        decision: &Decision,
+        view: Option<&DeckView>,
+    ) -> Result<usize, Interrupt> {
+        self.decisions
+            .decide(player, decision, view.or(Some(self.view)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::events::load_catalogue;
+    use super::*;
+
+    #[test]
+    fn test_scripted_answers_in_order() {
+        let options: [String; 3] = [String::from("a"), String::from("b"), String::from("c")];
+        let choice = Decision::Choice {
+            prompt: "Pick",
+            options: &options,
+        };
+        let mut scripted: Scripted = Scripted::new(vec![2, 0]);
+        assert_eq!(scripted.decide(Player::Dux, &choice, None).unwrap(), 2);
+        assert_eq!(scripted.decide(Player::Dux, &choice, None).unwrap(), 0);
+        assert_eq!(scripted.remaining(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_scripted_answers_run_out() {
+        let count = Decision::Count {
+            prompt: "How many",
+            max: 2,
+        };
+        let mut scripted: Scripted = Scripted::new(vec![3]);
+        assert_eq!(scripted.decide(Player::Civitates, &count, None).unwrap(), 3);
+        scripted.decide(Player::Civitates, &count, None).unwrap();
+    }
+
+    // Notes whether each question came with the cards
+    struct SeesCards(Vec<bool>);
+
+    impl DecisionProvider for SeesCards {
+        fn decide(
+            &mut self,
+            _: Player,
+            _: &Decision,
+            view: Option<&DeckView>,
+        ) -> Result<usize, Interrupt> {
+            self.0.push(view.is_some());
+            Ok(0)
+        }
+    }
+
+    #[test]
+    fn test_showing_cards_passes_the_view_on() {
+        let cards: Vec<Event> = load_catalogue().unwrap();
+        let view: DeckView = DeckView {
+            current: &cards[0],
+            pivotal_in_play: None,
+            upcoming: None,
+            cards_until_epoch: None,
+            discard: vec![],
+        };
+        let count = Decision::Count {
+            prompt: "How many",
+            max: 2,
+        };
+        let mut seen: SeesCards = SeesCards(vec![]);
+        let mut showing: ShowingCards = ShowingCards {
+            decisions: &mut seen,
+            view: &view,
+        };
+        showing.decide(Player::Dux, &count, None).unwrap();
+        showing.decide(Player::Dux, &count, Some(&view)).unwrap();
+        assert_eq!(seen.0, vec![true, true]);
+    }
+
+    #[test]
+    fn test_bot_stays_in_range_and_acts() {
+        let mut bot: Bot = Bot::new(7);
+        let options: [Action; 2] = [Action::Pass, Action::LimitedCommand];
+        for _ in 0..20 {
+            let action = Decision::Action { options: &options };
+            assert_eq!(bot.decide(Player::Scotti, &action, None).unwrap(), 1);
+            let count = Decision::Count {
+                prompt: "How many",
+                max: 3,
+            };
+            assert!(bot.decide(Player::Scotti, &count, None).unwrap() <= 3);
+        }
+    }
+}
@@ -0,0 +1,374 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::board::{Board, BoardError, Track};
+use super::commands::{CommandType, FeatType};
+use super::concepts::{Player, StrongholdClass, UnitClass};
+use super::decisions::Interrupt;
+use super::events::Event;
+use super::lasting::LastingEffect;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    Unshaded,
+    Shaded,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Side::Unshaded => write!(f, "Unshaded"),
+            Side::Shaded => write!(f, "Shaded"),
+        }
+    }
+}
+
+// One step of an event's text, applied to the board in order. Only the
+// arms in card_effects build these, and no card is transcribed yet.
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Effect {
+    PlaceUnits {
+        space: u8,
+        player: Player,
+        class: UnitClass,
+        count: u8,
+    },
+    RemoveUnits {
+        space: u8,
+        player: Player,
+        class: UnitClass,
+        count: u8,
+    },
+    PlaceStronghold {
+        space: u8,
+        player: Player,
+        class: StrongholdClass,
+    },
+    RemoveStronghold {
+        space: u8,
+        class: StrongholdClass,
+    },
+    ShiftTrack {
+        track: Track,
+        delta: i8,
+    },
+    ShiftProsperity {
+        space: u8,
+        delta: i8,
+    },
+    Ineligible(Player),
+    FreeCommand(Player, CommandType),
+    FreeFeat(Player, FeatType),
+    Lasting(LastingEffect),
+    RemoveMarker {
+        space: u8,
+        source: u8,
+    },
+}
+
+// What the sequence of play has to deal with once the board is updated
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventOutcome {
+    pub ineligible_next_card: Vec<Player>,
+    pub free_commands: Vec<(Player, CommandType)>,
+    pub free_feats: Vec<(Player, FeatType)>,
+}
+
+// Asked whenever the card text leaves a choice open: gets a prompt and the
+// options, returns the index of the one picked
+pub type Chooser<'a> = dyn FnMut(&str, &[String]) -> Result<usize, Interrupt> + 'a;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EventError {
+    Board(BoardError),
+    // The player broke off before anything was applied
+    Interrupted(Interrupt),
+    AnswerOutOfRange { answer: usize, answers: usize },
+    // The card's text has not been transcribed, so it can't be played
+    NotImplemented { name: String, side: Side },
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventError::Board(e) => write!(f, "{}", e),
+            EventError::Interrupted(interrupt) => write!(f, "Interrupted by {:?}", interrupt),
+            EventError::AnswerOutOfRange { answer, answers } => write!(
+                f,
+                "Answered {} but there are only {} options",
+                answer, answers
+            ),
+            EventError::NotImplemented { name, side } => {
+                write!(f, "{} ({}) can't be played yet", name, side)
+            }
+        }
+    }
+}
+
+impl From<BoardError> for EventError {
+    fn from(e: BoardError) -> Self {
+        EventError::Board(e)
+    }
+}
+
+// All choices are made before the board changes, so an interrupted event
+// leaves nothing behind. A board error part way through the effects does
+// leave the earlier ones applied, and the caller has to roll them back.
+pub fn resolve_event(
+    event: &Event,
+    side: Side,
+    board: &mut Board,
+    choose: &mut Chooser,
+) -> Result<EventOutcome, EventError> {
+    let mut outcome: EventOutcome = EventOutcome::default();
+    for effect in card_effects(event, side, board, choose)? {
+        apply(effect, board, &mut outcome)?;
+    }
+    return Ok(outcome);
+}
+
+// Card arms ask through this so an answer out of range is caught
+#[allow(dead_code)]
+fn pick(prompt: &str, options: &[String], choose: &mut Chooser) -> Result<usize, EventError> {
+    let answer: usize = choose(prompt, options).map_err(EventError::Interrupted)?;
+    if answer >= options.len() {
+        return Err(EventError::AnswerOutOfRange {
+            answer,
+            answers: options.len(),
+        });
+    }
+    return Ok(answer);
+}
+
+// Events do as much as they can: running out of pieces or sites is not an
+// error, it just means less happens
+pub fn apply(
+    effect: Effect,
+    board: &mut Board,
+    outcome: &mut EventOutcome,
+) -> Result<(), BoardError> {
+    match effect {
+        Effect::PlaceUnits {
+            space,
+            player,
+            class,
+            count,
+        } => {
+            board.place_from_available(space, player, class, count)?;
+        }
+        Effect::RemoveUnits {
+            space,
+            player,
+            class,
+            count,
+        } => {
+            board.remove_units(space, player, class, count)?;
+        }
+        Effect::PlaceStronghold {
+            space,
+            player,
+            class,
+        } => match board.place_stronghold_from_available(space, player, class) {
+            Ok(())
+            | Err(BoardError::NoneAvailable { .. })
+            | Err(BoardError::NoEmptySite { .. }) => {}
+            Err(e) => return Err(e),
+        },
+        Effect::RemoveStronghold { space, class } => match board.remove_stronghold_of(space, class)
+        {
+            Ok(_) | Err(BoardError::NoStronghold { .. }) => {}
+            Err(e) => return Err(e),
+        },
+        Effect::ShiftTrack { track, delta } => board.shift_track(track, delta),
+        Effect::ShiftProsperity { space, delta } => board.shift_prosperity(space, delta)?,
+        Effect::Ineligible(player) => outcome.ineligible_next_card.push(player),
+        Effect::FreeCommand(player, command) => outcome.free_commands.push((player, command)),
+        Effect::FreeFeat(player, feat) => outcome.free_feats.push((player, feat)),
+        Effect::Lasting(lasting) => board.add_lasting(lasting),
+        Effect::RemoveMarker { space, source } => {
+            board.remove_marker(space, source);
+        }
+    }
+    Ok(())
+}
+
+// Cards whose printed text card_effects carries out, by name. The card
+// text has not been transcribed into the tree yet, so there are none; a
+// card is only listed here once its arm below follows the printed card.
+const TRANSCRIBED: [&str; 0] = [];
+
+pub fn transcribed(event: &Event) -> bool {
+    TRANSCRIBED.contains(&event.name.as_str())
+}
+
+// Card text, keyed on the card's printed name. Anything not transcribed is
+// refused rather than guessed at, so it can't be played.
+pub fn card_effects(
+    event: &Event,
+    side: Side,
+    _board: &Board,
+    _choose: &mut Chooser,
+) -> Result<Vec<Effect>, EventError> {
+    return Err(EventError::NotImplemented {
+        name: event.name.clone(),
+        side,
+    });
+}
+
+// Whether a Pivotal event may be played on the board as it stands, keyed
+// on the card's printed name like card_effects. No Pivotal has been
+// transcribed yet, and one without its printed condition here is never
+// playable.
+pub fn pivotal_precondition(_event: &Event, _board: &Board) -> bool {
+    return false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::board::{REGNI, setup_barbarian_conspiracy};
+    use super::super::events::load_catalogue;
+    use super::super::lasting::{Expiry, LastingEffect, Modifier};
+    use super::*;
+
+    fn first(_: &str, _: &[String]) -> Result<usize, Interrupt> {
+        Ok(0)
+    }
+
+    #[test]
+    fn test_untranscribed_cards_are_refused() {
+        let board: Board = setup_barbarian_conspiracy();
+        for event in load_catalogue().unwrap() {
+            assert!(!transcribed(&event));
+            let mut played: Board = board.clone();
+            assert_eq!(
+                resolve_event(&event, Side::Shaded, &mut played, &mut first),
+                Err(EventError::NotImplemented {
+                    name: event.name.clone(),
+                    side: Side::Shaded
+                })
+            );
+            assert_eq!(played.track(Track::Prestige), board.track(Track::Prestige));
+        }
+    }
+
+    #[test]
+    fn test_effects_do_what_they_can() {
+        let mut board: Board = setup_barbarian_conspiracy();
+        let mut outcome: EventOutcome = EventOutcome::default();
+        let cavalry: u8 = board
+            .space(REGNI)
+            .unwrap()
+            .units_of(Player::Dux, UnitClass::Cavalry);
+        apply(
+            Effect::PlaceUnits {
+                space: REGNI,
+                player: Player::Dux,
+                class: UnitClass::Cavalry,
+                count: 2,
+            },
+            &mut board,
+            &mut outcome,
+        )
+        .unwrap();
+        assert_eq!(
+            board
+                .space(REGNI)
+                .unwrap()
+                .units_of(Player::Dux, UnitClass::Cavalry),
+            cavalry + 2
+        );
+
+        // Taking away a stronghold that isn't there does nothing
+        let fort = |b: &Board| {
+            b.space(REGNI)
+                .unwrap()
+                .strongholds_of(StrongholdClass::Fort)
+        };
+        let forts: u8 = fort(&board);
+        for _ in 0..=forts {
+            apply(
+                Effect::RemoveStronghold {
+                    space: REGNI,
+                    class: StrongholdClass::Fort,
+                },
+                &mut board,
+                &mut outcome,
+            )
+            .unwrap();
+        }
+        assert_eq!(fort(&board), 0);
+
+        // A space that isn't on the map is still an error
+        assert_eq!(
+            apply(
+                Effect::ShiftProsperity {
+                    space: 99,
+                    delta: 1
+                },
+                &mut board,
+                &mut outcome,
+            ),
+            Err(BoardError::NoSuchSpace(99))
+        );
+    }
+
+    #[test]
+    fn test_follow_ups_are_left_to_the_sequence_of_play() {
+        let mut board: Board = setup_barbarian_conspiracy();
+        let mut outcome: EventOutcome = EventOutcome::default();
+        for effect in [
+            Effect::Ineligible(Player::Scotti),
+            Effect::FreeCommand(Player::Dux, CommandType::Train),
+            Effect::FreeFeat(Player::Dux, FeatType::Requisition),
+        ] {
+            apply(effect, &mut board, &mut outcome).unwrap();
+        }
+        assert_eq!(outcome.ineligible_next_card, vec![Player::Scotti]);
+        assert_eq!(
+            outcome.free_commands,
+            vec![(Player::Dux, CommandType::Train)]
+        );
+        assert_eq!(
+            outcome.free_feats,
+            vec![(Player::Dux, FeatType::Requisition)]
+        );
+    }
+
+    #[test]
+    fn test_markers_come_off_with_their_effect() {
+        let mut board: Board = setup_barbarian_conspiracy();
+        let mut outcome: EventOutcome = EventOutcome::default();
+        let raid_cost = |b: &Board| {
+            b.lasting()
+                .command_cost_delta(Player::Saxons, CommandType::Raid, Some(REGNI))
+        };
+        apply(
+            Effect::Lasting(LastingEffect {
+                source: 3,
+                name: String::from("Marker"),
+                side: Side::Unshaded,
+                modifier: Modifier::CommandCost {
+                    player: Player::Saxons,
+                    command: CommandType::Raid,
+                    delta: 1,
+                },
+                expiry: Expiry::WhileMarker { space: REGNI },
+            }),
+            &mut board,
+            &mut outcome,
+        )
+        .unwrap();
+        assert_eq!(raid_cost(&board), 1);
+        apply(
+            Effect::RemoveMarker {
+                space: REGNI,
+                source: 3,
+            },
+            &mut board,
+            &mut outcome,
+        )
+        .unwrap();
+        assert_eq!(raid_cost(&board), 0);
+    }
+}
@@ -1,25 +1,261 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
 use super::concepts::Player;
-    use std::fmt;
-    // Cards
-    #[derive(Clone, Debug, PartialEq, Eq)]
-    pub enum EventType {
-        Standard,
-        Epoch,
-        Pivotal,
+
+const CATALOGUE: &str = include_str!("../data/events.toml");
+
+// Cards
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventType {
+    Standard,
+    Epoch,
+    Pivotal,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Event {
+    pub number: u8,
+    pub name: String,
+    pub eligibility: Vec<Player>,
+    pub unshaded: String,
+    pub shaded: String,
+    pub historical_notes: String,
+    pub event_type: EventType,
+    // Only Pivotal events belong to a faction
+    #[serde(default)]
+    pub faction: Option<Player>,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.eligibility)
+    }
+}
+
+impl Event {
+    // One line, as the card shows at a glance
+    pub fn title(&self) -> String {
+        match self.event_type {
+            EventType::Standard => format!("{} {} {:?}", self.number, self.name, self.eligibility),
+            EventType::Epoch => format!("{} {} (Epoch)", self.number, self.name),
+            EventType::Pivotal => format!("{} {} (Pivotal)", self.number, self.name),
+        }
     }
 
-    #[derive(Clone, Debug)]
-    pub struct Event {
-        pub name: String,
-        pub eligibility: Vec<Player>,
-        pub unshaded: Option<u8>,
-        pub shaded: Option<u8>,
-        pub historical_notes: String,
-        pub event_type: EventType,
+    // The whole card face
+    pub fn details(&self) -> String {
+        let mut text: String = self.title();
+        if !self.unshaded.is_empty() {
+            text.push_str(&format!("\n  Unshaded: {}", self.unshaded));
+        }
+        if !self.shaded.is_empty() {
+            text.push_str(&format!("\n  Shaded: {}", self.shaded));
+        }
+        if !self.historical_notes.is_empty() {
+            text.push_str(&format!("\n  {}", self.historical_notes));
+        }
+        return text;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CatalogueError {
+    Parse(String),
+    IncompleteEligibility { number: u8, name: String },
+    DuplicateNumber(u8),
+    PivotalWithoutFaction { number: u8, name: String },
+    DuplicatePivotal(Player),
+}
+
+impl fmt::Display for CatalogueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogueError::Parse(e) => write!(f, "Could not parse the event catalogue: {}", e),
+            CatalogueError::IncompleteEligibility { number, name } => write!(
+                f,
+                "Card {} ({}) needs an eligibility order naming all four factions",
+                number, name
+            ),
+            CatalogueError::DuplicateNumber(number) => {
+                write!(f, "More than one card is numbered {}", number)
+            }
+            CatalogueError::PivotalWithoutFaction { number, name } => {
+                write!(f, "Pivotal card {} ({}) has no faction", number, name)
+            }
+            CatalogueError::DuplicatePivotal(player) => {
+                write!(f, "{} hold more than one Pivotal event", player)
+            }
+        }
     }
+}
+
+#[derive(Deserialize)]
+struct Catalogue {
+    card: Vec<Event>,
+}
 
-    impl fmt::Display for Event {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            write!(f, "{:?}", self.eligibility)
+// The full card set, as embedded in the binary
+pub fn load_catalogue() -> Result<Vec<Event>, CatalogueError> {
+    parse_catalogue(CATALOGUE)
+}
+
+fn parse_catalogue(data: &str) -> Result<Vec<Event>, CatalogueError> {
+    let catalogue: Catalogue =
+        toml::from_str(data).map_err(|e| CatalogueError::Parse(e.to_string()))?;
+
+    let mut numbers: HashSet<u8> = HashSet::new();
+    let mut pivotal_factions: HashSet<Player> = HashSet::new();
+    for card in &catalogue.card {
+        if !numbers.insert(card.number) {
+            return Err(CatalogueError::DuplicateNumber(card.number));
+        }
+        if card.event_type == EventType::Standard {
+            let factions: HashSet<Player> = card.eligibility.iter().copied().collect();
+            if card.eligibility.len() != 4 || factions.len() != 4 {
+                return Err(CatalogueError::IncompleteEligibility {
+                    number: card.number,
+                    name: card.name.clone(),
+                });
+            }
+        }
+        if card.event_type == EventType::Pivotal {
+            match card.faction {
+                Some(player) => {
+                    if !pivotal_factions.insert(player) {
+                        return Err(CatalogueError::DuplicatePivotal(player));
+                    }
+                }
+                None => {
+                    return Err(CatalogueError::PivotalWithoutFaction {
+                        number: card.number,
+                        name: card.name.clone(),
+                    });
+                }
+            }
         }
-    }
\ No newline at end of file
+    }
+    return Ok(catalogue.card);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_catalogue_is_valid() {
+        let cards: Vec<Event> = load_catalogue().unwrap();
+        assert!(cards.iter().any(|e| e.event_type == EventType::Epoch));
+        let anderida: &Event = cards.iter().find(|e| e.name == "Anderida").unwrap();
+        assert_eq!(
+            anderida.eligibility,
+            vec![
+                Player::Saxons,
+                Player::Dux,
+                Player::Scotti,
+                Player::Civitates
+            ]
+        );
+    }
+
+    #[test]
+    fn test_catalogue_validation() {
+        let duplicate = r#"
+            [[card]]
+            number = 1
+            name = "One"
+            eligibility = []
+            unshaded = ""
+            shaded = ""
+            historical_notes = ""
+            event_type = "Epoch"
+
+            [[card]]
+            number = 1
+            name = "Also One"
+            eligibility = []
+            unshaded = ""
+            shaded = ""
+            historical_notes = ""
+            event_type = "Epoch"
+        "#;
+        assert_eq!(
+            parse_catalogue(duplicate).map(|c| c.len()),
+            Err(CatalogueError::DuplicateNumber(1))
+        );
+
+        let repeated_faction = r#"
+            [[card]]
+            number = 2
+            name = "Two"
+            eligibility = ["Dux", "Dux", "Saxons", "Scotti"]
+            unshaded = ""
+            shaded = ""
+            historical_notes = ""
+            event_type = "Standard"
+        "#;
+        assert_eq!(
+            parse_catalogue(repeated_faction).map(|c| c.len()),
+            Err(CatalogueError::IncompleteEligibility {
+                number: 2,
+                name: String::from("Two")
+            })
+        );
+
+        let pivotal = r#"
+            [[card]]
+            number = 4
+            name = "Four"
+            eligibility = []
+            unshaded = ""
+            shaded = ""
+            historical_notes = ""
+            event_type = "Pivotal"
+        "#;
+        assert_eq!(
+            parse_catalogue(pivotal).map(|c| c.len()),
+            Err(CatalogueError::PivotalWithoutFaction {
+                number: 4,
+                name: String::from("Four")
+            })
+        );
+
+        let two_for_the_dux = r#"
+            [[card]]
+            number = 5
+            name = "Five"
+            faction = "Dux"
+            eligibility = []
+            unshaded = ""
+            shaded = ""
+            historical_notes = ""
+            event_type = "Pivotal"
+
+            [[card]]
+            number = 6
+            name = "Six"
+            faction = "Dux"
+            eligibility = []
+            unshaded = ""
+            shaded = ""
+            historical_notes = ""
+            event_type = "Pivotal"
+        "#;
+        assert_eq!(
+            parse_catalogue(two_for_the_dux).map(|c| c.len()),
+            Err(CatalogueError::DuplicatePivotal(Player::Dux))
+        );
+
+        let missing_field = r#"
+            [[card]]
+            number = 3
+            name = "Three"
+            event_type = "Standard"
+        "#;
+        assert!(matches!(
+            parse_catalogue(missing_field),
+            Err(CatalogueError::Parse(_))
+        ));
+    }
+}
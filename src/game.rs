@@ -0,0 +1,281 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use std::cmp::Reverse;
+use std::fmt;
+
+use super::battle::Dice;
+use super::board::Board;
+use super::concepts::Player;
+use super::decisions::{Decision, DecisionProvider, Interrupt};
+use super::events::{Event, EventType};
+use super::inspect::DeckView;
+use super::journal::{self, Journal};
+use super::replay::{DecisionKind, LoggedDecision, Recorder};
+use super::sequence_of_play::{GameEnd, NextInput, SequenceOfPlay, SequenceOfPlayState, SopError};
+use super::setup::{DeckError, Scenario};
+
+// Everything needed to play one game: the board and the sequence of play
+// that drives it
+#[derive(Clone, Debug)]
+pub struct Game {
+    pub scenario: Scenario,
+    pub board: Board,
+    pub sop: SequenceOfPlay,
+    pub seed: u64,
+    pub rng: ChaCha8Rng,
+    pub journal: Journal,
+    // Every answer the game has been given, for replaying it
+    pub decisions: Vec<LoggedDecision>,
+}
+
+impl Game {
+    // The same scenario and seed always give the same game
+    pub fn new(scenario: Scenario, cards: Vec<Event>, seed: u64) -> Result<Self, DeckError> {
+        let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(seed);
+        let (pivotals, cards): (Vec<Event>, Vec<Event>) = cards
+            .into_iter()
+            .partition(|e| e.event_type == EventType::Pivotal);
+        let deck = scenario.deck(cards, &mut rng)?;
+        Ok(Game {
+            scenario,
+            board: scenario.board(),
+            sop: SequenceOfPlay::new(deck, pivotals),
+            seed,
+            rng,
+            journal: Journal::default(),
+            decisions: vec![],
+        })
+    }
+
+    pub fn next_input(&self) -> NextInput {
+        self.sop.next_input(&self.board)
+    }
+
+    // Takes one step of the sequence of play, asking `decisions` whatever
+    // that step needs, and says what the step after it will need. A failed
+    // step leaves the game as it was. Whatever happened goes in the journal,
+    // and the answers given in the decision log.
+    pub fn step(&mut self, decisions: &mut dyn DecisionProvider) -> Result<NextInput, SopError> {
+        let mut recorder: Recorder = Recorder::new(decisions);
+        let decisions: &mut dyn DecisionProvider = &mut recorder;
+        let state: SequenceOfPlayState = self.sop.state;
+        let board: Board = self.board.clone();
+        let rng: ChaCha8Rng = self.rng.clone();
+        let mut dice: Dice = Dice::new(&mut self.rng);
+        let before: SequenceOfPlay = self.sop.clone();
+        let sop: SequenceOfPlay = self.sop.clone();
+        let result: Result<SequenceOfPlay, SopError> = match self.sop.state {
+            SequenceOfPlayState::CheckEndRound => sop.check_end_round(),
+            SequenceOfPlayState::CheckPlayerStatus => sop.check_player_status(),
+            SequenceOfPlayState::ChoosingAction => sop.get_action(&self.board, decisions),
+            SequenceOfPlayState::Acting => sop.acting(&mut self.board, &mut dice, decisions),
+            SequenceOfPlayState::ResetEligibility => sop.reset_eligibility(),
+            SequenceOfPlayState::AdvanceEvents => sop.advance_events(&mut self.board),
+            SequenceOfPlayState::OfferPivotal => sop.offer_pivotal(&self.board, decisions),
+            SequenceOfPlayState::ResolvingPivotal => {
+                sop.resolve_pivotal(&mut self.board, &mut dice, decisions)
+            }
+            SequenceOfPlayState::Epoch => sop.epoch(&mut self.board),
+            SequenceOfPlayState::GameOver => Ok(sop),
+        };
+        let rolled: Vec<u8> = dice.rolled;
+        // Commands change the board and roll dice as they go, so one broken
+        // off part way has to be put back
+        self.sop = match result {
+            Ok(sop) => sop,
+            Err(e) => {
+                self.board = board;
+                self.rng = rng;
+                return Err(e);
+            }
+        };
+        self.decisions.append(&mut recorder.answers);
+        journal::record_step(self, state, &before, &board, rolled);
+        return Ok(self.next_input());
+    }
+
+    // Takes every step that needs no input and stops where one does, or at
+    // the end of the game
+    pub fn run_until_decision(&mut self) -> Result<NextInput, SopError> {
+        loop {
+            match self.next_input() {
+                NextInput::Automatic => {
+                    self.step(&mut NoDecisions)?;
+                }
+                input => return Ok(input),
+            }
+        }
+    }
+
+    // After a recoverable error the same faction is asked again, so the
+    // action (and side) it chose no longer belong in the decision log
+    pub fn ask_again(&mut self) {
+        if self.sop.state == SequenceOfPlayState::Acting {
+            if self.last_decision_is(DecisionKind::Side) {
+                self.decisions.pop();
+            }
+            if self.last_decision_is(DecisionKind::Action) {
+                self.decisions.pop();
+            }
+        }
+        self.sop = self.sop.clone().ask_again();
+    }
+
+    fn last_decision_is(&self, kind: DecisionKind) -> bool {
+        self.decisions.last().is_some_and(|d| d.kind == kind)
+    }
+}
+
+// Stands in for a player during steps that never ask anything. A step
+// that does ask is stopped rather than answered.
+struct NoDecisions;
+
+impl DecisionProvider for NoDecisions {
+    fn decide(
+        &mut self,
+        _: Player,
+        _: &Decision,
+        _: Option<&DeckView>,
+    ) -> Result<usize, Interrupt> {
+        Err(Interrupt::Stop)
+    }
+}
+
+// Each faction's margin against its victory threshold once the game is
+// over, best first. Ties go to the faction listed first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FinalResult {
+    pub end: GameEnd,
+    pub standings: Vec<(Player, i16)>,
+}
+
+impl FinalResult {
+    pub fn winner(&self) -> Player {
+        self.standings[0].0
+    }
+}
+
+impl fmt::Display for FinalResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.end {
+            GameEnd::FinalEpoch => writeln!(f, "The game ends after the final Epoch")?,
+            GameEnd::DeckExhausted => writeln!(f, "The game ends with the deck exhausted")?,
+        }
+        for (player, margin) in &self.standings {
+            writeln!(f, "  {}: {:+}", player, margin)?;
+        }
+        write!(f, "{} win", self.winner())
+    }
+}
+
+impl Game {
+    pub fn final_result(&self) -> Option<FinalResult> {
+        let end: GameEnd = self.sop.end()?;
+        let mut standings: Vec<(Player, i16)> = Player::ALL
+            .iter()
+            .map(|p| (*p, self.board.victory_margin(*p)))
+            .collect();
+        standings.sort_by_key(|(_, score)| Reverse(*score));
+        return Some(FinalResult { end, standings });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::decisions::{Bot, Scripted};
+    use super::super::events::load_catalogue;
+    use super::super::sequence_of_play::SequenceOfPlayState;
+    use super::*;
+
+    #[test]
+    fn test_every_scenario_starts() {
+        for scenario in Scenario::ALL {
+            let game: Game = Game::new(scenario, load_catalogue().unwrap(), 0).unwrap();
+            assert_eq!(game.sop.state, SequenceOfPlayState::OfferPivotal);
+            assert!(game.board.spaces().count() > 0);
+        }
+    }
+
+    #[test]
+    fn test_final_result() {
+        let mut game: Game =
+            Game::new(Scenario::EttyTyrants, load_catalogue().unwrap(), 0).unwrap();
+        assert!(game.final_result().is_none());
+        while game.sop.state != SequenceOfPlayState::GameOver {
+            match game.sop.state {
+                SequenceOfPlayState::Epoch => game.sop = game.sop.epoch(&mut game.board).unwrap(),
+                _ => {
+                    game.sop.state = SequenceOfPlayState::AdvanceEvents;
+                    game.sop = game.sop.advance_events(&mut game.board).unwrap();
+                }
+            }
+        }
+        let result: FinalResult = game.final_result().unwrap();
+        assert_eq!(result.end, GameEnd::FinalEpoch);
+        assert_eq!(
+            result.standings,
+            vec![
+                (Player::Dux, 21),
+                (Player::Civitates, 4),
+                (Player::Scotti, -8),
+                (Player::Saxons, -10),
+            ]
+        );
+        assert_eq!(result.winner(), Player::Dux);
+    }
+
+    #[test]
+    fn test_bots_play_a_whole_game() {
+        let mut game: Game =
+            Game::new(Scenario::BarbarianConspiracy, load_catalogue().unwrap(), 3).unwrap();
+        let mut decisions: Bot = Bot::new(3);
+        let mut cards: usize = 0;
+        loop {
+            if game.sop.state == SequenceOfPlayState::AdvanceEvents {
+                cards += 1;
+            }
+            if game.step(&mut decisions).unwrap() == NextInput::GameOver {
+                break;
+            }
+        }
+        assert!(cards > 1);
+        assert!(game.final_result().is_some());
+    }
+
+    #[test]
+    fn test_run_until_decision() {
+        let mut game: Game =
+            Game::new(Scenario::DeExcidioBritanniae, load_catalogue().unwrap(), 0).unwrap();
+        let first: Player = game.sop.deck_view().current.eligibility[0];
+        // No Pivotal event can be played yet, so the first faction is asked
+        assert_eq!(game.run_until_decision().unwrap(), NextInput::Action(first));
+        assert_eq!(game.sop.state, SequenceOfPlayState::ChoosingAction);
+
+        // Passing needs nothing more, so the next faction is asked
+        let second: Player = game.sop.deck_view().current.eligibility[1];
+        assert_eq!(
+            game.step(&mut Scripted::new(vec![0])).unwrap(),
+            NextInput::Automatic
+        );
+        assert_eq!(
+            game.run_until_decision().unwrap(),
+            NextInput::Action(second)
+        );
+
+        // A step that asks something is stopped, not answered
+        let count = Decision::Count {
+            prompt: "How many",
+            max: 1,
+        };
+        assert_eq!(
+            NoDecisions.decide(first, &count, None),
+            Err(Interrupt::Stop)
+        );
+
+        // A bad answer leaves the game where it was
+        let before: SequenceOfPlayState = game.sop.state;
+        assert!(game.step(&mut Scripted::new(vec![9])).is_err());
+        assert_eq!(game.sop.state, before);
+    }
+}
@@ -0,0 +1,232 @@
+use std::fmt;
+
+use super::game::Game;
+
+// How far back undo may go, counted in cards revealed since the snapshot
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UndoLimit {
+    CurrentCard,
+    Cards(usize),
+}
+
+// Things that revealed information the players should not be able to take
+// back without meaning to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Barrier {
+    DieRoll,
+    CardReveal,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryError {
+    NothingToUndo,
+    NothingToRedo,
+    BeyondLimit(UndoLimit),
+    // Undo would cross this; ask before forcing it
+    Barrier(Barrier),
+}
+
+impl fmt::Display for Barrier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Barrier::DieRoll => write!(f, "a die roll"),
+            Barrier::CardReveal => write!(f, "a card reveal"),
+        }
+    }
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryError::NothingToUndo => write!(f, "Nothing to undo"),
+            HistoryError::NothingToRedo => write!(f, "Nothing to redo"),
+            HistoryError::BeyondLimit(UndoLimit::CurrentCard) => {
+                write!(f, "Can only undo actions on the current card")
+            }
+            HistoryError::BeyondLimit(UndoLimit::Cards(n)) => {
+                write!(f, "Can only undo actions on the last {} cards", n + 1)
+            }
+            HistoryError::Barrier(barrier) => write!(f, "Undoing would take back {}", barrier),
+        }
+    }
+}
+
+// Snapshots of the whole game (board, sequence of play and RNG) taken
+// before each decision
+pub struct History {
+    undo: Vec<Game>,
+    redo: Vec<Game>,
+    limit: UndoLimit,
+}
+
+fn cards_revealed(from: &Game, to: &Game) -> usize {
+    from.sop.cards_left().saturating_sub(to.sop.cards_left())
+}
+
+fn barrier_between(from: &Game, to: &Game) -> Option<Barrier> {
+    if cards_revealed(from, to) > 0 {
+        return Some(Barrier::CardReveal);
+    }
+    if from.rng.get_word_pos() != to.rng.get_word_pos() {
+        return Some(Barrier::DieRoll);
+    }
+    return None;
+}
+
+impl History {
+    pub fn new(limit: UndoLimit) -> Self {
+        History {
+            undo: vec![],
+            redo: vec![],
+            limit,
+        }
+    }
+
+    fn within_limit(&self, snapshot: &Game, game: &Game) -> bool {
+        return match self.limit {
+            UndoLimit::CurrentCard => cards_revealed(snapshot, game) == 0,
+            UndoLimit::Cards(n) => cards_revealed(snapshot, game) <= n,
+        };
+    }
+
+    // Takes the game as it was before a decision that has now been made.
+    // Making a new decision drops anything that could have been redone.
+    pub fn record(&mut self, before: Game, now: &Game) {
+        self.undo.push(before);
+        self.redo.clear();
+        let snapshots: Vec<Game> = std::mem::take(&mut self.undo);
+        self.undo = snapshots
+            .into_iter()
+            .filter(|snapshot| self.within_limit(snapshot, now))
+            .collect();
+    }
+
+    #[cfg(test)]
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    // Goes back to just before the last decision. `force` is the player
+    // confirming they want to cross a die roll or card reveal.
+    pub fn undo(&mut self, game: &mut Game, force: bool) -> Result<(), HistoryError> {
+        let snapshot: &Game = self.undo.last().ok_or(HistoryError::NothingToUndo)?;
+        if !self.within_limit(snapshot, game) {
+            return Err(HistoryError::BeyondLimit(self.limit));
+        }
+        if let Some(barrier) = barrier_between(snapshot, game).filter(|_| !force) {
+            return Err(HistoryError::Barrier(barrier));
+        }
+        let snapshot: Game = self.undo.pop().unwrap();
+        self.redo.push(std::mem::replace(game, snapshot));
+        return Ok(());
+    }
+
+    pub fn redo(&mut self, game: &mut Game) -> Result<(), HistoryError> {
+        let snapshot: Game = self.redo.pop().ok_or(HistoryError::NothingToRedo)?;
+        self.undo.push(std::mem::replace(game, snapshot));
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngCore;
+
+    use super::super::decisions::Scripted;
+    use super::super::events::load_catalogue;
+    use super::super::sequence_of_play::{NextInput, SequenceOfPlayState};
+    use super::super::setup::Scenario;
+    use super::*;
+
+    // Plays one decision the way the game loop does, recording it
+    fn decide(game: &mut Game, history: &mut History, answers: Vec<usize>) {
+        let before: Game = game.clone();
+        game.step(&mut Scripted::new(answers)).unwrap();
+        history.record(before, game);
+        game.run_until_decision().unwrap();
+    }
+
+    fn new_game() -> Game {
+        let mut game: Game =
+            Game::new(Scenario::EttyTyrants, load_catalogue().unwrap(), 0).unwrap();
+        game.run_until_decision().unwrap();
+        game
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut game: Game = new_game();
+        let mut history: History = History::new(UndoLimit::CurrentCard);
+        while let NextInput::Pivotal(players) = game.next_input() {
+            decide(&mut game, &mut history, vec![0; players.len()]);
+        }
+        let start: SequenceOfPlayState = game.sop.state;
+        let first = game.next_input();
+
+        // Pass, then undo it and redo it
+        decide(&mut game, &mut history, vec![0]);
+        let after = game.next_input();
+        assert_ne!(after, first);
+        history.undo(&mut game, false).unwrap();
+        assert_eq!(game.sop.state, start);
+        assert_eq!(game.next_input(), first);
+        history.redo(&mut game).unwrap();
+        assert_eq!(game.next_input(), after);
+        assert_eq!(history.redo(&mut game), Err(HistoryError::NothingToRedo));
+
+        // Undoing and choosing again drops the redo
+        history.undo(&mut game, false).unwrap();
+        decide(&mut game, &mut history, vec![0]);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn test_undo_stops_at_the_current_card() {
+        let mut game: Game = new_game();
+        let mut history: History = History::new(UndoLimit::CurrentCard);
+        // Every faction passes until the card ends and the next is revealed
+        let cards: usize = game.sop.cards_left();
+        while game.sop.cards_left() == cards {
+            let answers: usize = match game.next_input() {
+                NextInput::Pivotal(players) => players.len(),
+                _ => 1,
+            };
+            decide(&mut game, &mut history, vec![0; answers]);
+        }
+        assert_eq!(
+            history.undo(&mut game, true),
+            Err(HistoryError::BeyondLimit(UndoLimit::CurrentCard))
+        );
+    }
+
+    #[test]
+    fn test_barriers_need_forcing() {
+        let mut game: Game = new_game();
+        let mut history: History = History::new(UndoLimit::CurrentCard);
+        let before: Game = game.clone();
+        game.rng.next_u32();
+        history.record(before.clone(), &game);
+        assert_eq!(
+            history.undo(&mut game, false),
+            Err(HistoryError::Barrier(Barrier::DieRoll))
+        );
+        history.undo(&mut game, true).unwrap();
+        assert_eq!(game.rng.get_word_pos(), before.rng.get_word_pos());
+
+        let mut limited: History = History::new(UndoLimit::Cards(1));
+        let revealed: Game = {
+            let mut g: Game = game.clone();
+            g.sop.state = SequenceOfPlayState::AdvanceEvents;
+            g.step(&mut Scripted::new(vec![])).unwrap();
+            g
+        };
+        limited.record(game.clone(), &revealed);
+        let mut now: Game = revealed.clone();
+        assert_eq!(
+            limited.undo(&mut now, false),
+            Err(HistoryError::Barrier(Barrier::CardReveal))
+        );
+        limited.undo(&mut now, true).unwrap();
+        assert_eq!(now.sop.cards_left(), game.sop.cards_left());
+    }
+}
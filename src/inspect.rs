@@ -0,0 +1,99 @@
+use dialoguer::Select;
+
+use super::events::Event;
+
+// What the players can look at from any prompt: the current and upcoming
+// cards, the run-up to the next Epoch and the discard pile
+pub struct DeckView<'a> {
+    pub current: &'a Event,
+    pub pivotal_in_play: Option<&'a Event>,
+    pub upcoming: Option<&'a Event>,
+    pub cards_until_epoch: Option<usize>,
+    pub discard: Vec<&'a Event>,
+}
+
+const VIEW_CARDS: &str = "View cards";
+
+impl DeckView<'_> {
+    pub fn summary(&self) -> String {
+        let mut text: String = format!("Current card: {}", self.current.title());
+        if let Some(pivotal) = self.pivotal_in_play {
+            text.push_str(&format!("\nPre-empted by: {}", pivotal.title()));
+        }
+        match self.upcoming {
+            Some(event) => text.push_str(&format!("\nUpcoming card: {}", event.title())),
+            None => text.push_str("\nUpcoming card: none, the deck is empty"),
+        }
+        match self.cards_until_epoch {
+            Some(0) => text.push_str("\nThe Epoch is next"),
+            Some(n) => text.push_str(&format!("\n{} cards before the next Epoch", n)),
+            None => text.push_str("\nNo Epochs left in the deck"),
+        }
+        text.push_str(&format!(
+            "\n{} cards in the discard pile",
+            self.discard.len()
+        ));
+        return text;
+    }
+
+    pub fn browse(&self) -> dialoguer::Result<()> {
+        loop {
+            let options: [&str; 4] = [
+                "Current and upcoming card",
+                "Current card in full",
+                "Discard pile",
+                "Back",
+            ];
+            match Select::new()
+                .with_prompt("Look at which cards?")
+                .items(&options)
+                .default(0)
+                .interact()?
+            {
+                0 => println!("{}", self.summary()),
+                1 => println!("{}", self.current.details()),
+                2 => self.browse_discard()?,
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    // Most recently discarded first
+    fn browse_discard(&self) -> dialoguer::Result<()> {
+        if self.discard.is_empty() {
+            println!("The discard pile is empty");
+            return Ok(());
+        }
+        let mut titles: Vec<String> = self.discard.iter().map(|e| e.title()).collect();
+        titles.push(String::from("Back"));
+        loop {
+            let picked: usize = Select::new()
+                .with_prompt("Discard pile")
+                .items(&titles)
+                .default(0)
+                .interact()?;
+            match self.discard.get(picked) {
+                Some(event) => println!("{}", event.details()),
+                None => return Ok(()),
+            }
+        }
+    }
+}
+
+// A Select with one extra entry for looking at the cards, which returns to
+// the same prompt afterwards
+pub fn select(prompt: &str, items: &[String], view: &DeckView) -> dialoguer::Result<usize> {
+    let mut options: Vec<String> = items.to_vec();
+    options.push(String::from(VIEW_CARDS));
+    loop {
+        let picked: usize = Select::new()
+            .with_prompt(prompt)
+            .items(&options)
+            .default(0)
+            .interact()?;
+        if picked < items.len() {
+            return Ok(picked);
+        }
+        view.browse()?;
+    }
+}
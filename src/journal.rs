@@ -0,0 +1,399 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use super::board::{Board, Track};
+use super::concepts::{Player, StrongholdClass, UnitClass};
+use super::effects::Side;
+use super::events::EventType;
+use super::game::Game;
+use super::lasting::LastingEffect;
+use super::sequence_of_play::{Action, GameEnd, PlayerState, SequenceOfPlay, SequenceOfPlayState};
+
+// When something happened. Rounds are counted from 1 and end with their
+// Epoch card; cards count every card revealed so far, the first being 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stamp {
+    pub round: u8,
+    pub card: u16,
+    // The number printed on the card
+    pub number: u8,
+}
+
+impl Stamp {
+    pub fn of(sop: &SequenceOfPlay) -> Self {
+        let view = sop.deck_view();
+        let played = || {
+            view.discard
+                .iter()
+                .filter(|e| e.event_type != EventType::Pivotal)
+        };
+        Stamp {
+            round: played()
+                .filter(|e| e.event_type == EventType::Epoch)
+                .count() as u8
+                + 1,
+            card: played().count() as u16 + 1,
+            number: view.current.number,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Happening {
+    CardRevealed { name: String },
+    Action { action: Action, side: Option<Side> },
+    Pivotal { name: String },
+    EligibilityReset { ineligible: Vec<Player> },
+    Expired { name: String },
+    Epoch,
+    GameOver(GameEnd),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Piece {
+    Unit(UnitClass),
+    Stronghold(StrongholdClass),
+}
+
+// Pieces of one kind gained (positive) or lost in a space. A move shows up
+// as a loss in one space and a gain in another.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PieceChange {
+    pub space: String,
+    pub player: Player,
+    pub piece: Piece,
+    pub delta: i8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackChange {
+    pub track: Track,
+    pub from: u8,
+    pub to: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub stamp: Stamp,
+    pub faction: Option<Player>,
+    pub what: Happening,
+    pub pieces: Vec<PieceChange>,
+    pub dice: Vec<u8>,
+    pub tracks: Vec<TrackChange>,
+}
+
+impl Entry {
+    pub fn new(stamp: Stamp, faction: Option<Player>, what: Happening) -> Self {
+        Entry {
+            stamp,
+            faction,
+            what,
+            pieces: vec![],
+            dice: vec![],
+            tracks: vec![],
+        }
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[Round {}, card {} (#{})] ",
+            self.stamp.round, self.stamp.card, self.stamp.number
+        )?;
+        if let Some(faction) = self.faction {
+            write!(f, "{}: ", faction)?;
+        }
+        match &self.what {
+            Happening::CardRevealed { name } => write!(f, "{} revealed", name)?,
+            Happening::Action { action, side: None } => write!(f, "{}", action)?,
+            Happening::Action {
+                action,
+                side: Some(side),
+            } => write!(f, "{} ({})", action, side)?,
+            Happening::Pivotal { name } => write!(f, "plays {}", name)?,
+            Happening::EligibilityReset { ineligible } if ineligible.is_empty() => {
+                write!(f, "Everyone Eligible")?
+            }
+            Happening::EligibilityReset { ineligible } => {
+                write!(f, "Ineligible for the next card: {:?}", ineligible)?
+            }
+            Happening::Expired { name } => write!(f, "{} no longer in effect", name)?,
+            Happening::Epoch => write!(f, "Epoch")?,
+            Happening::GameOver(end) => write!(f, "Game over ({:?})", end)?,
+        }
+        for change in &self.pieces {
+            write!(
+                f,
+                "\n    {:+} {} {:?} in {}",
+                change.delta, change.player, change.piece, change.space
+            )?;
+        }
+        if !self.dice.is_empty() {
+            write!(f, "\n    rolled {:?}", self.dice)?;
+        }
+        for change in &self.tracks {
+            write!(
+                f,
+                "\n    {:?} {} -> {}",
+                change.track, change.from, change.to
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Every action and state change in the game so far, oldest first
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Journal {
+    entries: Vec<Entry>,
+}
+
+// How the journal is written out: one [[entry]] table each
+#[derive(Serialize)]
+struct Export<'a> {
+    entry: &'a [Entry],
+}
+
+impl Journal {
+    pub fn record(&mut self, entry: Entry) {
+        self.entries.push(entry);
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn card(&self, card: u16) -> Vec<&Entry> {
+        self.entries
+            .iter()
+            .filter(|e| e.stamp.card == card)
+            .collect()
+    }
+
+    // The card before the one now in play
+    pub fn last_card(&self) -> Vec<&Entry> {
+        match self.entries.last() {
+            Some(latest) if latest.stamp.card > 1 => self.card(latest.stamp.card - 1),
+            _ => vec![],
+        }
+    }
+
+    pub fn faction(&self, player: Player) -> Vec<&Entry> {
+        self.entries
+            .iter()
+            .filter(|e| e.faction == Some(player))
+            .collect()
+    }
+
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string(&Export {
+            entry: &self.entries,
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn export(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_toml()?).map_err(|e| e.to_string())
+    }
+}
+
+fn piece_counts(board: &Board) -> BTreeMap<(u8, Player, Piece), i8> {
+    let mut counts: BTreeMap<(u8, Player, Piece), i8> = BTreeMap::new();
+    for space in board.spaces() {
+        for unit in &space.units {
+            *counts
+                .entry((space.id, unit.controller, Piece::Unit(unit.designation)))
+                .or_default() += 1;
+        }
+        for stronghold in space
+            .stronghold_sites
+            .iter()
+            .filter_map(|s| s.stronghold.as_ref())
+        {
+            *counts
+                .entry((
+                    space.id,
+                    stronghold.controller,
+                    Piece::Stronghold(stronghold.class),
+                ))
+                .or_default() += 1;
+        }
+    }
+    return counts;
+}
+
+pub fn piece_changes(before: &Board, after: &Board) -> Vec<PieceChange> {
+    let mut counts: BTreeMap<(u8, Player, Piece), i8> = piece_counts(after);
+    for (key, count) in piece_counts(before) {
+        *counts.entry(key).or_default() -= count;
+    }
+    counts
+        .into_iter()
+        .filter(|(_, delta)| *delta != 0)
+        .map(|((space, player, piece), delta)| PieceChange {
+            space: after
+                .space(space)
+                .map_or(space.to_string(), |s| s.name.clone()),
+            player,
+            piece,
+            delta,
+        })
+        .collect()
+}
+
+pub fn track_changes(before: &Board, after: &Board) -> Vec<TrackChange> {
+    Track::ALL
+        .iter()
+        .map(|track| TrackChange {
+            track: *track,
+            from: before.track(*track),
+            to: after.track(*track),
+        })
+        .filter(|change| change.from != change.to)
+        .collect()
+}
+
+// Writes up the step the game has just taken from `state`, given the board
+// and sequence of play as they were before it and the dice it rolled
+pub fn record_step(
+    game: &mut Game,
+    state: SequenceOfPlayState,
+    sop: &SequenceOfPlay,
+    board: &Board,
+    dice: Vec<u8>,
+) {
+    let stamp: Stamp = Stamp::of(&game.sop);
+    let expired: Vec<&LastingEffect> = board
+        .lasting()
+        .active()
+        .iter()
+        .filter(|e| !game.board.lasting().active().contains(e))
+        .collect();
+    for effect in expired {
+        game.journal.record(Entry::new(
+            stamp,
+            None,
+            Happening::Expired {
+                name: effect.name.clone(),
+            },
+        ));
+    }
+
+    let (faction, what): (Option<Player>, Happening) = match state {
+        SequenceOfPlayState::AdvanceEvents => match game.sop.end() {
+            Some(end) => (None, Happening::GameOver(end)),
+            None => (
+                None,
+                Happening::CardRevealed {
+                    name: game.sop.deck_view().current.name.clone(),
+                },
+            ),
+        },
+        SequenceOfPlayState::Acting => match sop.selected() {
+            (Some(action), side) => (sop.faction_to_act(), Happening::Action { action, side }),
+            (None, _) => return,
+        },
+        SequenceOfPlayState::ResolvingPivotal => (
+            sop.pivotal_player(),
+            Happening::Pivotal {
+                name: game
+                    .sop
+                    .deck_view()
+                    .pivotal_in_play
+                    .map_or(String::new(), |e| e.name.clone()),
+            },
+        ),
+        SequenceOfPlayState::ResetEligibility => (
+            None,
+            Happening::EligibilityReset {
+                ineligible: Player::ALL
+                    .into_iter()
+                    .filter(|p| game.sop.eligibility(*p) == Some(PlayerState::Ineligible))
+                    .collect(),
+            },
+        ),
+        SequenceOfPlayState::Epoch => (None, Happening::Epoch),
+        _ => return,
+    };
+    let mut entry: Entry = Entry::new(stamp, faction, what);
+    entry.pieces = piece_changes(board, &game.board);
+    entry.dice = dice;
+    entry.tracks = track_changes(board, &game.board);
+    game.journal.record(entry);
+
+    // The last Epoch ends the game then and there
+    if state == SequenceOfPlayState::Epoch
+        && let Some(end) = game.sop.end()
+    {
+        game.journal
+            .record(Entry::new(stamp, None, Happening::GameOver(end)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::board::{REGNI, setup_barbarian_conspiracy};
+    use super::super::concepts::Unit;
+    use super::super::decisions::Bot;
+    use super::super::events::load_catalogue;
+    use super::super::setup::Scenario;
+    use super::*;
+
+    #[test]
+    fn test_changes_between_boards() {
+        let before: Board = setup_barbarian_conspiracy();
+        let mut after: Board = before.clone();
+        after.place_units(REGNI, Unit::con_militia(2)).unwrap();
+        after.shift_track(Track::Prestige, 2);
+        let pieces: Vec<PieceChange> = piece_changes(&before, &after);
+        assert_eq!(pieces.len(), 1);
+        assert_eq!(pieces[0].delta, 2);
+        assert_eq!(pieces[0].piece, Piece::Unit(UnitClass::Militia));
+        assert_eq!(
+            track_changes(&before, &after),
+            vec![TrackChange {
+                track: Track::Prestige,
+                from: before.track(Track::Prestige),
+                to: before.track(Track::Prestige) + 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_journal_of_a_game() {
+        let mut game: Game =
+            Game::new(Scenario::EttyTyrants, load_catalogue().unwrap(), 2).unwrap();
+        let mut bot: Bot = Bot::new(2);
+        while game.step(&mut bot).unwrap() != super::super::sequence_of_play::NextInput::GameOver {}
+
+        let entries: &[Entry] = game.journal.entries();
+        assert!(matches!(
+            entries.last().unwrap().what,
+            Happening::GameOver(_)
+        ));
+        assert!(
+            entries
+                .windows(2)
+                .all(|w| w[0].stamp.card <= w[1].stamp.card)
+        );
+        assert!(
+            entries
+                .iter()
+                .any(|e| matches!(e.what, Happening::CardRevealed { .. }))
+        );
+        assert!(!game.journal.last_card().is_empty());
+        assert!(
+            game.journal
+                .faction(Player::Dux)
+                .iter()
+                .all(|e| e.faction == Some(Player::Dux))
+        );
+        let exported: String = game.journal.to_toml().unwrap();
+        assert_eq!(exported.matches("[[entry]]").count(), entries.len());
+    }
+}
@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::commands::{CommandType, FeatType};
+use super::concepts::Player;
+use super::effects::Side;
+
+// What an active Capability or Momentum event changes
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Modifier {
+    CommandCost {
+        player: Player,
+        command: CommandType,
+        delta: i8,
+    },
+    CommandBlocked {
+        player: Player,
+        command: CommandType,
+    },
+    FeatBlocked {
+        player: Player,
+        feat: FeatType,
+    },
+    BattleLosses {
+        player: Player,
+        delta: i8,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Expiry {
+    // Momentum: removed at the next Epoch round
+    UntilEpoch,
+    // Capability: stays for the rest of the game
+    Permanent,
+    // Lasts while its marker sits in the space
+    WhileMarker { space: u8 },
+    // Removed after this many more card boundaries
+    Cards(u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Boundary {
+    EndOfCard,
+    Epoch,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastingEffect {
+    pub source: u8,
+    pub name: String,
+    pub side: Side,
+    pub modifier: Modifier,
+    pub expiry: Expiry,
+}
+
+impl LastingEffect {
+    fn applies_in(&self, space: Option<u8>) -> bool {
+        match self.expiry {
+            Expiry::WhileMarker { space: marker } => space == Some(marker),
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for LastingEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}, card {}): {:?}, {:?}",
+            self.name, self.side, self.source, self.modifier, self.expiry
+        )
+    }
+}
+
+// Every lasting effect currently in play. Commands, feats and battles ask
+// here whether anything changes what they are about to do.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LastingEffects {
+    active: Vec<LastingEffect>,
+}
+
+impl LastingEffects {
+    pub fn add(&mut self, effect: LastingEffect) {
+        self.active.push(effect);
+    }
+
+    pub fn active(&self) -> &[LastingEffect] {
+        &self.active
+    }
+
+    // Returns what expired so it can be reported
+    pub fn expire(&mut self, boundary: Boundary) -> Vec<LastingEffect> {
+        let mut expired: Vec<LastingEffect> = vec![];
+        let mut kept: Vec<LastingEffect> = vec![];
+        for mut effect in self.active.drain(..) {
+            let ends: bool = match (&mut effect.expiry, boundary) {
+                (Expiry::UntilEpoch, Boundary::Epoch) => true,
+                (Expiry::Cards(left), Boundary::EndOfCard) => {
+                    *left = left.saturating_sub(1);
+                    *left == 0
+                }
+                _ => false,
+            };
+            if ends {
+                expired.push(effect);
+            } else {
+                kept.push(effect);
+            }
+        }
+        self.active = kept;
+        return expired;
+    }
+
+    pub fn remove_marker(&mut self, space: u8, source: u8) -> Option<LastingEffect> {
+        let position = self
+            .active
+            .iter()
+            .position(|e| e.source == source && e.expiry == Expiry::WhileMarker { space })?;
+        return Some(self.active.remove(position));
+    }
+
+    pub fn command_cost_delta(
+        &self,
+        player: Player,
+        command: CommandType,
+        space: Option<u8>,
+    ) -> i8 {
+        self.active
+            .iter()
+            .filter(|e| e.applies_in(space))
+            .map(|e| match e.modifier {
+                Modifier::CommandCost {
+                    player: p,
+                    command: c,
+                    delta,
+                } if p == player && c == command => delta,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    pub fn command_blocked(&self, player: Player, command: CommandType) -> bool {
+        self.active
+            .iter()
+            .any(|e| e.modifier == Modifier::CommandBlocked { player, command })
+    }
+
+    pub fn feat_blocked(&self, player: Player, feat: FeatType) -> bool {
+        self.active
+            .iter()
+            .any(|e| e.modifier == Modifier::FeatBlocked { player, feat })
+    }
+
+    pub fn battle_losses_delta(&self, player: Player, space: u8) -> i8 {
+        self.active
+            .iter()
+            .filter(|e| e.applies_in(Some(space)))
+            .map(|e| match e.modifier {
+                Modifier::BattleLosses { player: p, delta } if p == player => delta,
+                _ => 0,
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn effect(modifier: Modifier, expiry: Expiry) -> LastingEffect {
+        LastingEffect {
+            source: 1,
+            name: String::from("Test"),
+            side: Side::Unshaded,
+            modifier,
+            expiry,
+        }
+    }
+
+    #[test]
+    fn test_expiry() {
+        let mut lasting: LastingEffects = LastingEffects::default();
+        let blocked = Modifier::CommandBlocked {
+            player: Player::Dux,
+            command: CommandType::Intercept,
+        };
+        lasting.add(effect(blocked.clone(), Expiry::UntilEpoch));
+        lasting.add(effect(
+            Modifier::BattleLosses {
+                player: Player::Civitates,
+                delta: 1,
+            },
+            Expiry::Permanent,
+        ));
+        lasting.add(effect(
+            Modifier::FeatBlocked {
+                player: Player::Saxons,
+                feat: FeatType::Ravage,
+            },
+            Expiry::Cards(2),
+        ));
+
+        assert!(lasting.expire(Boundary::EndOfCard).is_empty());
+        assert!(lasting.feat_blocked(Player::Saxons, FeatType::Ravage));
+        assert_eq!(lasting.expire(Boundary::EndOfCard).len(), 1);
+        assert!(!lasting.feat_blocked(Player::Saxons, FeatType::Ravage));
+
+        assert!(lasting.command_blocked(Player::Dux, CommandType::Intercept));
+        let expired = lasting.expire(Boundary::Epoch);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].modifier, blocked);
+        assert!(!lasting.command_blocked(Player::Dux, CommandType::Intercept));
+
+        assert_eq!(lasting.battle_losses_delta(Player::Civitates, 0), 1);
+        assert_eq!(lasting.active().len(), 1);
+    }
+
+    #[test]
+    fn test_marker_effects_only_apply_in_their_space() {
+        let mut lasting: LastingEffects = LastingEffects::default();
+        lasting.add(effect(
+            Modifier::CommandCost {
+                player: Player::Saxons,
+                command: CommandType::Raid,
+                delta: 1,
+            },
+            Expiry::WhileMarker { space: 18 },
+        ));
+        assert_eq!(
+            lasting.command_cost_delta(Player::Saxons, CommandType::Raid, Some(18)),
+            1
+        );
+        assert_eq!(
+            lasting.command_cost_delta(Player::Saxons, CommandType::Raid, Some(2)),
+            0
+        );
+        assert!(lasting.expire(Boundary::Epoch).is_empty());
+
+        assert!(lasting.remove_marker(18, 1).is_some());
+        assert_eq!(
+            lasting.command_cost_delta(Player::Saxons, CommandType::Raid, Some(18)),
+            0
+        );
+    }
+}
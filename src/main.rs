@@ -1,42 +1,314 @@
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use cli::{Cli, Command, PlayArgs, SimulateArgs};
+use concepts::Player;
+use decisions::{Bot, Interrupt, Seats};
+use dialoguer::{Confirm, Input, Select};
 use events::Event;
-use sequence_of_play::{SequenceOfPlay, SequenceOfPlayState};
-use std::collections::VecDeque;
+use game::Game;
+use history::{History, HistoryError, UndoLimit};
+use journal::Entry;
+use replay::{DecisionLog, Replayed};
+use sequence_of_play::{NextInput, SopError};
+use setup::Scenario;
+use std::collections::BTreeMap;
+use std::path::Path;
 
+mod battle;
+mod board;
+mod cli;
+mod commands;
 mod concepts;
+mod decisions;
+mod effects;
 mod events;
+mod game;
+mod history;
+mod inspect;
+mod journal;
+mod lasting;
+mod narrate;
+mod replay;
+mod save;
 mod sequence_of_play;
 mod setup;
-mod commands;
-mod board;
 
 fn main() {
-    let deck: VecDeque<Event> = setup::build_deck();
-    let mut sop: SequenceOfPlay = SequenceOfPlay::new(deck);
+    let cli: Cli = Cli::parse();
+    if cli.no_color {
+        colored::control::set_override(false);
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+    let cards: Vec<Event> = match events::load_catalogue() {
+        Ok(cards) => cards,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    match cli.command.unwrap_or(Command::Play(cli.play)) {
+        Command::Play(args) => play(args, cards),
+        Command::Replay { log } => run_replay(&log, cards),
+        Command::Simulate(args) => simulate(args, cards),
+    }
+}
+
+// Bad arguments are turned away before anything is dealt or asked
+fn invalid(message: String) -> ! {
+    Cli::command()
+        .error(ErrorKind::ArgumentConflict, message)
+        .exit()
+}
+
+fn play(args: PlayArgs, cards: Vec<Event>) {
+    let humans: Vec<Player> = args.humans().unwrap_or_else(|e| invalid(e));
+    let mut game: Game = match &args.load {
+        Some(path) => match save::load(path) {
+            Ok(game) => {
+                println!("Resuming {} from {}", game.scenario, path.display());
+                game
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        None => {
+            let scenario: Scenario = match args.scenario {
+                Some(scenario) => scenario,
+                None => match setup::choose_scenario() {
+                    Ok(scenario) => scenario,
+                    Err(e) => {
+                        eprintln!("No scenario chosen: {}", e);
+                        std::process::exit(1);
+                    }
+                },
+            };
+            let seed: u64 = args.seed.unwrap_or_else(rand::random);
+            println!("Starting {} with seed {}", scenario, seed);
+            match Game::new(scenario, cards, seed) {
+                Ok(game) => game,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    };
+    let mut seats: Seats = Seats::new(humans, game.seed);
+    let mut history: History = History::new(match args.undo_cards {
+        0 => UndoLimit::CurrentCard,
+        n => UndoLimit::Cards(n),
+    });
     loop {
-        match sop.state {
-            SequenceOfPlayState::CheckEndRound => {
-                sop = sop.check_end_round();
+        let input: NextInput = match game.run_until_decision() {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("The game cannot continue: {}", e);
+                write_log(&game, Some(&e));
+                std::process::exit(1);
+            }
+        };
+        if input == NextInput::GameOver {
+            if let Some(result) = game.final_result() {
+                println!("{}", result);
+            }
+            write_log(&game, None);
+            break;
+        }
+        // Only what people chose can be undone; bots just choose again
+        let before: Option<Game> = match input {
+            NextInput::Action(p) | NextInput::Resolving(p) if seats.is_human(p) => {
+                Some(game.clone())
             }
-            SequenceOfPlayState::CheckPlayerStatus => {
-                sop = sop.check_player_status();
+            NextInput::Pivotal(players) if players.iter().any(|p| seats.is_human(*p)) => {
+                Some(game.clone())
             }
-            SequenceOfPlayState::ChoosingAction => {
-                sop = sop.get_action();
+            _ => None,
+        };
+        match game.step(&mut seats) {
+            Ok(_) => {
+                if let Some(before) = before {
+                    history.record(before, &game);
+                }
             }
-            SequenceOfPlayState::Acting => {
-                sop = sop.acting();
+            Err(SopError::Interrupted(Interrupt::Undo)) => undo(&mut history, &mut game),
+            Err(SopError::Interrupted(Interrupt::Save)) => save_game(&game),
+            Err(SopError::Interrupted(Interrupt::Journal)) => show_journal(&game),
+            Err(SopError::Interrupted(Interrupt::Redo)) => {
+                if let Err(e) = history.redo(&mut game) {
+                    eprintln!("{}", e);
+                }
             }
-            SequenceOfPlayState::ResetEligibility => {
-                sop = sop.reset_eligibility();
+            Err(e) if e.is_recoverable() => {
+                eprintln!("{}, choose again", e);
+                game.ask_again();
             }
-            SequenceOfPlayState::AdvanceEvents => {
-                sop = sop.advance_events();
+            Err(e) => {
+                eprintln!("The game cannot continue: {}", e);
+                write_log(&game, Some(&e));
+                std::process::exit(1);
             }
-            SequenceOfPlayState::Epoch => {
-                sop.epoch();
-                println!("Only one Epoch so far, more to come later");
-                break;
+        }
+    }
+}
+
+// Plays each game to the end with bots for every faction and only reports
+// the results
+fn simulate(args: SimulateArgs, cards: Vec<Event>) {
+    if let Err(e) = args.validate() {
+        invalid(e);
+    }
+    narrate::set_quiet(true);
+    let first: u64 = args.seed.unwrap_or_else(|| rand::random::<u32>() as u64);
+    let mut wins: BTreeMap<Player, u32> = BTreeMap::new();
+    for i in 0..args.games {
+        let seed: u64 = first + i as u64;
+        let mut game: Game = match Game::new(args.scenario, cards.clone(), seed) {
+            Ok(game) => game,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
             }
         };
+        let mut bot: Bot = Bot::new(seed);
+        loop {
+            match game.step(&mut bot) {
+                Ok(NextInput::GameOver) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    eprintln!("Game {} (seed {}) stopped: {}", i + 1, seed, e);
+                    break;
+                }
+            }
+        }
+        if let Some(result) = game.final_result() {
+            println!("Game {} (seed {}): {} win", i + 1, seed, result.winner());
+            *wins.entry(result.winner()).or_default() += 1;
+        }
+    }
+    println!("\nWins over {} games of {}", args.games, args.scenario);
+    for player in Player::ALL {
+        println!("  {}: {}", player, wins.get(&player).copied().unwrap_or(0));
+    }
+}
+
+// Written whenever a game ends or breaks, so it can be played again
+fn write_log(game: &Game, failure: Option<&SopError>) {
+    let path: String = DecisionLog::file_name(game);
+    match DecisionLog::of(game, failure).and_then(|log| log.write(Path::new(&path))) {
+        Ok(()) => println!("Decision log written to {}", path),
+        Err(e) => eprintln!("{}", e),
+    }
+}
+
+fn run_replay(path: &Path, cards: Vec<Event>) {
+    let result = DecisionLog::read(path).and_then(|log| {
+        println!(
+            "Replaying {} decisions of {} with seed {}",
+            log.decision.len(),
+            log.scenario,
+            log.seed
+        );
+        replay::replay(&log, cards)
+    });
+    match result {
+        Ok(Replayed {
+            reproduced: Some(e),
+            ..
+        }) => println!("The replay reproduced the logged failure: {}", e),
+        Ok(Replayed { game, .. }) => {
+            println!("The replay reached the logged state");
+            if let Some(result) = game.final_result() {
+                println!("{}", result);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// Crossing a die roll or card reveal has to be confirmed
+fn undo(history: &mut History, game: &mut Game) {
+    match history.undo(game, false) {
+        Ok(()) => {}
+        Err(HistoryError::Barrier(barrier)) => {
+            let confirmed: bool = Confirm::new()
+                .with_prompt(format!("Undo past {}?", barrier))
+                .default(false)
+                .interact()
+                .unwrap_or(false);
+            if confirmed && let Err(e) = history.undo(game, true) {
+                eprintln!("{}", e);
+            }
+        }
+        Err(e) => eprintln!("{}", e),
+    }
+}
+
+fn save_game(game: &Game) {
+    let path: String = Input::new()
+        .with_prompt("Save to")
+        .default(String::from("pdg-save.toml"))
+        .interact_text()
+        .unwrap_or_default();
+    match save::save(game, Path::new(&path)) {
+        Ok(()) => println!("Saved to {}, resume with --load {}", path, path),
+        Err(e) => eprintln!("{}", e),
+    }
+}
+
+fn show_journal(game: &Game) {
+    let options: [&str; 5] = [
+        "What happened last card?",
+        "This card so far",
+        "One faction's actions",
+        "Everything",
+        "Export to a file",
+    ];
+    let Ok(picked) = Select::new()
+        .with_prompt("Journal")
+        .items(&options)
+        .default(0)
+        .interact()
+    else {
+        return;
+    };
+    let card: u16 = journal::Stamp::of(&game.sop).card;
+    let entries: Vec<&Entry> = match picked {
+        0 => game.journal.last_card(),
+        1 => game.journal.card(card),
+        2 => {
+            let Ok(faction) = Select::new()
+                .with_prompt("Which faction?")
+                .items(&Player::ALL)
+                .interact()
+            else {
+                return;
+            };
+            game.journal.faction(Player::ALL[faction])
+        }
+        3 => game.journal.entries().iter().collect(),
+        _ => {
+            let path: String = Input::new()
+                .with_prompt("Export to")
+                .default(String::from("pdg-journal.toml"))
+                .interact_text()
+                .unwrap_or_default();
+            match game.journal.export(Path::new(&path)) {
+                Ok(()) => println!("Journal written to {}", path),
+                Err(e) => eprintln!("Could not write the journal: {}", e),
+            }
+            return;
+        }
+    };
+    if entries.is_empty() {
+        println!("Nothing yet");
+    }
+    for entry in entries {
+        println!("{}", entry);
     }
 }
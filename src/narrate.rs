@@ -0,0 +1,23 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Play-by-play output for whoever is watching the game. Headless
+// simulations turn it off; prompts and errors are always shown.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+macro_rules! narrate {
+    ($($arg:tt)*) => {
+        if !$crate::narrate::is_quiet() {
+            println!($($arg)*);
+        }
+    };
+}
+
+pub(crate) use narrate;
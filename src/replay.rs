@@ -0,0 +1,427 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use super::concepts::Player;
+use super::decisions::{Decision, DecisionProvider, Interrupt};
+use super::events::Event;
+use super::game::Game;
+use super::inspect::DeckView;
+use super::journal::Stamp;
+use super::save;
+use super::sequence_of_play::{NextInput, SequenceOfPlayState, SopError};
+use super::setup::{DeckError, Scenario};
+
+// Bump whenever the layout of a decision log changes
+pub const LOG_VERSION: u32 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DecisionKind {
+    Action,
+    Side,
+    Pivotal,
+    Choice,
+    Count,
+}
+
+impl DecisionKind {
+    fn of(decision: &Decision) -> Self {
+        match decision {
+            Decision::Action { .. } => DecisionKind::Action,
+            Decision::Side { .. } => DecisionKind::Side,
+            Decision::Pivotal { .. } => DecisionKind::Pivotal,
+            Decision::Choice { .. } => DecisionKind::Choice,
+            Decision::Count { .. } => DecisionKind::Count,
+        }
+    }
+}
+
+// One answer the game received, with how many it could have been
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoggedDecision {
+    pub player: Player,
+    pub kind: DecisionKind,
+    pub answer: usize,
+    pub answers: usize,
+}
+
+// Passes decisions through to whoever makes them and notes the answers
+pub struct Recorder<'a> {
+    inner: &'a mut dyn DecisionProvider,
+    pub answers: Vec<LoggedDecision>,
+}
+
+impl<'a> Recorder<'a> {
+    pub fn new(inner: &'a mut dyn DecisionProvider) -> Self {
+        Recorder {
+            inner,
+            answers: vec![],
+        }
+    }
+}
+
+impl DecisionProvider for Recorder<'_> {
+    fn decide(
+        &mut self,
+        player: Player,
+        decision: &Decision,
+        view: Option<&DeckView>,
+    ) -> Result<usize, Interrupt> {
+        let answer: usize = self.inner.decide(player, decision, view)?;
+        self.answers.push(LoggedDecision {
+            player,
+            kind: DecisionKind::of(decision),
+            answer,
+            answers: decision.answers(),
+        });
+        return Ok(answer);
+    }
+}
+
+// Where the game had got to when the log was written. The digest covers
+// the whole saved state; the rest is there to say what differs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub stamp: Stamp,
+    pub state: SequenceOfPlayState,
+    pub cards_left: usize,
+    pub digest: String,
+}
+
+// FNV-1a, which unlike the standard library's hasher is the same on every
+// build
+fn digest(data: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in data.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    return format!("{:016x}", hash);
+}
+
+impl Checkpoint {
+    pub fn of(game: &Game) -> Result<Self, ReplayError> {
+        Ok(Checkpoint {
+            stamp: Stamp::of(&game.sop),
+            state: game.sop.state,
+            cards_left: game.sop.cards_left(),
+            digest: digest(&save::to_string(game).map_err(|e| ReplayError::File(e.to_string()))?),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DecisionLog {
+    pub version: u32,
+    pub scenario: Scenario,
+    // As text, since TOML integers are signed
+    pub seed: String,
+    pub checkpoint: Checkpoint,
+    // Why the game stopped, when it broke rather than ended
+    pub failure: Option<String>,
+    pub decision: Vec<LoggedDecision>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    File(String),
+    Version {
+        found: u32,
+    },
+    // The game asked for something other than what was logged next
+    Mismatch {
+        index: usize,
+        logged: LoggedDecision,
+        player: Player,
+        kind: DecisionKind,
+        answers: usize,
+    },
+    // Asked for more than the log holds; a replay ends here
+    LogEnded {
+        index: usize,
+    },
+    // The game could not go on, as it could not when the log was written
+    Stopped(SopError),
+    Deck(DeckError),
+    Diverged {
+        expected: Checkpoint,
+        found: Checkpoint,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::File(e) => write!(f, "Could not use the decision log: {}", e),
+            ReplayError::Version { found } => write!(
+                f,
+                "The decision log is version {} but this build only replays version {}",
+                found, LOG_VERSION
+            ),
+            ReplayError::Mismatch {
+                index,
+                logged,
+                player,
+                kind,
+                answers,
+            } => write!(
+                f,
+                "Decision {} no longer matches: the log has {} answering {:?} with {} of {}, but the game asks {} for {:?} with {} options",
+                index + 1,
+                logged.player,
+                logged.kind,
+                logged.answer,
+                logged.answers,
+                player,
+                kind,
+                answers
+            ),
+            ReplayError::LogEnded { index } => {
+                write!(f, "The log ran out at decision {}", index + 1)
+            }
+            ReplayError::Stopped(e) => write!(f, "The replay stopped: {}", e),
+            ReplayError::Deck(e) => write!(f, "Could not deal the deck: {}", e),
+            ReplayError::Diverged { expected, found } => write!(
+                f,
+                "The replay ended in a different state: expected {:?} on card {} (#{}) with {} cards left, found {:?} on card {} (#{}) with {} cards left{}",
+                expected.state,
+                expected.stamp.card,
+                expected.stamp.number,
+                expected.cards_left,
+                found.state,
+                found.stamp.card,
+                found.stamp.number,
+                found.cards_left,
+                if expected.digest != found.digest {
+                    " and a different board"
+                } else {
+                    ""
+                }
+            ),
+        }
+    }
+}
+
+impl DecisionLog {
+    pub fn of(game: &Game, failure: Option<&SopError>) -> Result<Self, ReplayError> {
+        Ok(DecisionLog {
+            version: LOG_VERSION,
+            scenario: game.scenario,
+            seed: game.seed.to_string(),
+            checkpoint: Checkpoint::of(game)?,
+            failure: failure.map(|e| e.to_string()),
+            decision: game.decisions.clone(),
+        })
+    }
+
+    // Named for the scenario and seed, so logs of different games do not
+    // overwrite each other
+    pub fn file_name(game: &Game) -> String {
+        format!("pdg-replay-{}-{}.toml", game.scenario.key(), game.seed)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), ReplayError> {
+        let data: String = toml::to_string(self).map_err(|e| ReplayError::File(e.to_string()))?;
+        fs::write(path, data).map_err(|e| ReplayError::File(e.to_string()))
+    }
+
+    pub fn read(path: &Path) -> Result<Self, ReplayError> {
+        let data: String =
+            fs::read_to_string(path).map_err(|e| ReplayError::File(e.to_string()))?;
+        DecisionLog::parse(&data)
+    }
+
+    pub fn parse(data: &str) -> Result<Self, ReplayError> {
+        #[derive(Deserialize)]
+        struct Header {
+            version: u32,
+        }
+        let header: Header = toml::from_str(data).map_err(|e| ReplayError::File(e.to_string()))?;
+        if header.version != LOG_VERSION {
+            return Err(ReplayError::Version {
+                found: header.version,
+            });
+        }
+        toml::from_str(data).map_err(|e| ReplayError::File(e.to_string()))
+    }
+}
+
+// Gives the logged answers back in order, checking each still fits the
+// question being asked
+pub struct Replayer {
+    log: Vec<LoggedDecision>,
+    next: usize,
+    pub error: Option<ReplayError>,
+}
+
+impl Replayer {
+    pub fn new(log: Vec<LoggedDecision>) -> Self {
+        Replayer {
+            log,
+            next: 0,
+            error: None,
+        }
+    }
+}
+
+impl DecisionProvider for Replayer {
+    fn decide(
+        &mut self,
+        player: Player,
+        decision: &Decision,
+        _: Option<&DeckView>,
+    ) -> Result<usize, Interrupt> {
+        let Some(logged) = self.log.get(self.next).copied() else {
+            self.error = Some(ReplayError::LogEnded { index: self.next });
+            return Err(Interrupt::Stop);
+        };
+        let kind: DecisionKind = DecisionKind::of(decision);
+        let answers: usize = decision.answers();
+        if logged.player != player
+            || logged.kind != kind
+            || logged.answers != answers
+            || logged.answer >= answers
+        {
+            self.error = Some(ReplayError::Mismatch {
+                index: self.next,
+                logged,
+                player,
+                kind,
+                answers,
+            });
+            return Err(Interrupt::Stop);
+        }
+        self.next += 1;
+        return Ok(logged.answer);
+    }
+}
+
+// Where a replay got to, and the failure the log was written for if the
+// replay ran into it again
+pub struct Replayed {
+    pub game: Game,
+    pub reproduced: Option<SopError>,
+}
+
+// Plays the log through from the start and checks the game ends up where
+// it was when the log was written
+pub fn replay(log: &DecisionLog, cards: Vec<Event>) -> Result<Replayed, ReplayError> {
+    let seed: u64 = log
+        .seed
+        .parse()
+        .map_err(|_| ReplayError::File(format!("bad seed {}", log.seed)))?;
+    let mut game: Game = Game::new(log.scenario, cards, seed).map_err(ReplayError::Deck)?;
+    let mut replayer: Replayer = Replayer::new(log.decision.clone());
+    let mut reproduced: Option<SopError> = None;
+    // Runs until the game asks for more than the log holds, or ends
+    loop {
+        match game.step(&mut replayer) {
+            Ok(NextInput::GameOver) => break,
+            Ok(_) => {}
+            Err(SopError::Interrupted(Interrupt::Stop)) => match replayer.error.take() {
+                Some(ReplayError::LogEnded { .. }) | None => break,
+                Some(e) => return Err(e),
+            },
+            // The failure the log was written for, met again once every
+            // logged answer has been given, is what a replay is for
+            Err(e)
+                if replayer.next == log.decision.len()
+                    && log.failure.as_deref() == Some(e.to_string().as_str()) =>
+            {
+                reproduced = Some(e);
+                break;
+            }
+            Err(e) => return Err(ReplayError::Stopped(e)),
+        }
+    }
+    let found: Checkpoint = Checkpoint::of(&game)?;
+    if found != log.checkpoint {
+        return Err(ReplayError::Diverged {
+            expected: log.checkpoint.clone(),
+            found,
+        });
+    }
+    return Ok(Replayed { game, reproduced });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::decisions::Bot;
+    use super::super::events::load_catalogue;
+    use super::*;
+
+    fn played(steps: usize) -> Game {
+        let mut game: Game =
+            Game::new(Scenario::TheHarderTheyFall, load_catalogue().unwrap(), 11).unwrap();
+        let mut bot: Bot = Bot::new(11);
+        for _ in 0..steps {
+            if game.step(&mut bot).unwrap() == NextInput::GameOver {
+                break;
+            }
+        }
+        game.run_until_decision().unwrap();
+        game
+    }
+
+    #[test]
+    fn test_replay_reaches_the_same_state() {
+        let game: Game = played(200);
+        assert!(!game.decisions.is_empty());
+        let log: DecisionLog =
+            DecisionLog::parse(&toml::to_string(&DecisionLog::of(&game, None).unwrap()).unwrap())
+                .unwrap();
+        let replayed: Replayed = replay(&log, load_catalogue().unwrap()).unwrap();
+        assert!(replayed.reproduced.is_none());
+        let replayed: Game = replayed.game;
+        assert_eq!(
+            save::to_string(&replayed).unwrap(),
+            save::to_string(&game).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_choices_asked_again_are_not_replayed() {
+        let mut game: Game =
+            Game::new(Scenario::TheHarderTheyFall, load_catalogue().unwrap(), 11).unwrap();
+        let mut bot: Bot = Bot::new(11);
+        while game.sop.state != SequenceOfPlayState::Acting {
+            game.step(&mut bot).unwrap();
+        }
+        // As after a command that could not be carried out
+        let logged: usize = game.decisions.len();
+        game.ask_again();
+        assert!(game.decisions.len() < logged);
+        assert_eq!(game.sop.state, SequenceOfPlayState::ChoosingAction);
+        for _ in 0..100 {
+            if game.step(&mut bot).unwrap() == NextInput::GameOver {
+                break;
+            }
+        }
+        game.run_until_decision().unwrap();
+
+        let log: DecisionLog = DecisionLog::of(&game, None).unwrap();
+        let replayed: Replayed = replay(&log, load_catalogue().unwrap()).unwrap();
+        assert_eq!(
+            save::to_string(&replayed.game).unwrap(),
+            save::to_string(&game).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_replay_stops_at_the_first_mismatch() {
+        let mut log: DecisionLog = DecisionLog::of(&played(200), None).unwrap();
+        log.decision[3].answer = log.decision[3].answers;
+        assert!(matches!(
+            replay(&log, load_catalogue().unwrap()),
+            Err(ReplayError::Mismatch { index: 3, .. })
+        ));
+
+        let mut log: DecisionLog = DecisionLog::of(&played(200), None).unwrap();
+        log.checkpoint.digest = String::from("0");
+        assert!(matches!(
+            replay(&log, load_catalogue().unwrap()),
+            Err(ReplayError::Diverged { .. })
+        ));
+    }
+}
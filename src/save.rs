@@ -0,0 +1,242 @@
+use rand_chacha::ChaCha8Rng;
+use rand_chacha::rand_core::SeedableRng;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use super::board::Board;
+use super::game::Game;
+use super::journal::Journal;
+use super::replay::LoggedDecision;
+use super::sequence_of_play::SequenceOfPlay;
+use super::setup::Scenario;
+
+// Bump whenever the layout of a save changes; files written with any other
+// version are refused rather than half read
+pub const SAVE_VERSION: u32 = 2;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SaveError {
+    Io { path: String, error: String },
+    Parse(String),
+    Serialize(String),
+    Version { found: u32 },
+    Invalid(String),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io { path, error } => write!(f, "Could not access {}: {}", path, error),
+            SaveError::Parse(e) => write!(f, "Could not read the save: {}", e),
+            SaveError::Serialize(e) => write!(f, "Could not write the save: {}", e),
+            SaveError::Version { found } => write!(
+                f,
+                "The save is version {} but this build only reads version {}",
+                found, SAVE_VERSION
+            ),
+            SaveError::Invalid(reason) => write!(f, "The save is not a valid game: {}", reason),
+        }
+    }
+}
+
+// Read on its own first, so an old save gets a version error rather than
+// whatever part of it no longer parses
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+// TOML has no 128 bit integers and only signed 64 bit ones, so the RNG is
+// written out as text
+#[derive(Serialize, Deserialize)]
+struct SavedRng {
+    seed: String,
+    stream: String,
+    word_pos: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    scenario: Scenario,
+    seed: String,
+    rng: SavedRng,
+    sop: SequenceOfPlay,
+    board: Board,
+    journal: Journal,
+    decisions: Vec<LoggedDecision>,
+}
+
+impl SavedRng {
+    fn new(rng: &ChaCha8Rng) -> Self {
+        SavedRng {
+            seed: rng
+                .get_seed()
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+            stream: rng.get_stream().to_string(),
+            word_pos: rng.get_word_pos().to_string(),
+        }
+    }
+
+    fn restore(&self) -> Result<ChaCha8Rng, SaveError> {
+        let invalid = |field: &str| SaveError::Invalid(format!("bad RNG {}", field));
+        if self.seed.len() != 64 || !self.seed.is_ascii() {
+            return Err(invalid("seed"));
+        }
+        let mut seed: [u8; 32] = [0; 32];
+        for (i, byte) in seed.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&self.seed[i * 2..i * 2 + 2], 16)
+                .map_err(|_| invalid("seed"))?;
+        }
+        let mut rng: ChaCha8Rng = ChaCha8Rng::from_seed(seed);
+        rng.set_stream(self.stream.parse().map_err(|_| invalid("stream"))?);
+        rng.set_word_pos(self.word_pos.parse().map_err(|_| invalid("position"))?);
+        return Ok(rng);
+    }
+}
+
+pub fn to_string(game: &Game) -> Result<String, SaveError> {
+    let file: SaveFile = SaveFile {
+        version: SAVE_VERSION,
+        scenario: game.scenario,
+        seed: game.seed.to_string(),
+        rng: SavedRng::new(&game.rng),
+        sop: game.sop.clone(),
+        board: game.board.clone(),
+        journal: game.journal.clone(),
+        decisions: game.decisions.clone(),
+    };
+    toml::to_string(&file).map_err(|e| SaveError::Serialize(e.to_string()))
+}
+
+pub fn from_str(data: &str) -> Result<Game, SaveError> {
+    let header: Header = toml::from_str(data).map_err(|e| SaveError::Parse(e.to_string()))?;
+    if header.version != SAVE_VERSION {
+        return Err(SaveError::Version {
+            found: header.version,
+        });
+    }
+    let file: SaveFile = toml::from_str(data).map_err(|e| SaveError::Parse(e.to_string()))?;
+
+    let mut numbers: HashSet<u8> = HashSet::new();
+    for number in file.sop.card_numbers() {
+        if !numbers.insert(number) {
+            return Err(SaveError::Invalid(format!(
+                "card {} is in more than one place",
+                number
+            )));
+        }
+    }
+    let seed: u64 = file
+        .seed
+        .parse()
+        .map_err(|_| SaveError::Invalid(format!("bad seed {}", file.seed)))?;
+    return Ok(Game {
+        scenario: file.scenario,
+        board: file.board,
+        sop: file.sop,
+        seed,
+        rng: file.rng.restore()?,
+        journal: file.journal,
+        decisions: file.decisions,
+    });
+}
+
+pub fn save(game: &Game, path: &Path) -> Result<(), SaveError> {
+    fs::write(path, to_string(game)?).map_err(|e| SaveError::Io {
+        path: path.display().to_string(),
+        error: e.to_string(),
+    })
+}
+
+pub fn load(path: &Path) -> Result<Game, SaveError> {
+    let data: String = fs::read_to_string(path).map_err(|e| SaveError::Io {
+        path: path.display().to_string(),
+        error: e.to_string(),
+    })?;
+    from_str(&data)
+}
+
+// Writes a map in key order so the same game always saves the same way
+pub fn sorted<S: Serializer, K: Serialize + Ord, V: Serialize>(
+    map: &HashMap<K, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let sorted: BTreeMap<&K, &V> = map.iter().collect();
+    sorted.serialize(serializer)
+}
+
+// Maps keyed by space id, written with the ids as table names since TOML
+// keys have to be strings
+pub mod by_id {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::{BTreeMap, HashMap};
+
+    pub fn serialize<S: Serializer, T: Serialize>(
+        map: &HashMap<u8, T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let sorted: BTreeMap<String, &T> = map.iter().map(|(id, v)| (id.to_string(), v)).collect();
+        sorted.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<u8, T>, D::Error> {
+        let named: BTreeMap<String, T> = BTreeMap::deserialize(deserializer)?;
+        named
+            .into_iter()
+            .map(|(id, v)| {
+                id.parse::<u8>()
+                    .map(|id| (id, v))
+                    .map_err(|_| D::Error::custom(format!("{} is not an id", id)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngCore;
+
+    use super::super::decisions::Bot;
+    use super::super::events::load_catalogue;
+    use super::*;
+
+    fn game_in_progress() -> Game {
+        let mut game: Game =
+            Game::new(Scenario::BarbarianConspiracy, load_catalogue().unwrap(), 5).unwrap();
+        let mut bot: Bot = Bot::new(5);
+        for _ in 0..40 {
+            game.step(&mut bot).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut game: Game = game_in_progress();
+        let saved: String = to_string(&game).unwrap();
+        let mut loaded: Game = from_str(&saved).unwrap();
+        assert_eq!(to_string(&loaded).unwrap(), saved);
+        assert_eq!(loaded.sop.state, game.sop.state);
+        assert_eq!(loaded.next_input(), game.next_input());
+        assert_eq!(loaded.rng.next_u64(), game.rng.next_u64());
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        let saved: String = to_string(&game_in_progress()).unwrap();
+        let old: String = saved.replacen(&format!("version = {}", SAVE_VERSION), "version = 0", 1);
+        assert_eq!(from_str(&old).unwrap_err(), SaveError::Version { found: 0 });
+        assert!(matches!(from_str("not a save"), Err(SaveError::Parse(_))));
+        let broken: String = saved.replacen("word_pos = \"", "word_pos = \"x", 1);
+        assert!(matches!(from_str(&broken), Err(SaveError::Invalid(_))));
+    }
+}
@@ -1,23 +1,40 @@
-use dialoguer::Select;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::fmt;
 
+use super::battle::Dice;
+use super::board::{Board, BoardError};
+use super::commands::{self, CommandError, CommandType, FeatType};
 use super::concepts::Player;
+use super::decisions::{Decision, DecisionProvider, Interrupt, SIDES, ShowingCards};
+use super::effects::{self, EventError, EventOutcome, Side};
 use super::events::{Event, EventType};
+use super::inspect::DeckView;
+use super::lasting::{Boundary, LastingEffect};
+use super::narrate::narrate;
+use super::save;
 use Player::{Civitates, Dux, Saxons, Scotti};
 
 use PlayerState::Eligible;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+// Where the card is in the 1st/2nd Eligible sequence. Passing leaves the
+// state as it is, so whoever is next in the eligibility order takes the
+// passing faction's place.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 enum AvailableActionState {
-    Start,
-    A,
-    B,
-    C,
+    // Nobody has acted yet
+    FirstEligible,
+    // The 1st Eligible faction did a Command without a Feat
+    AfterCommandOnly,
+    // The 1st Eligible faction did a Command with a Feat
+    AfterCommandFeat,
+    // The 1st Eligible faction played the Event
+    AfterEvent,
+    // Both Eligible factions have acted
     End,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct AvailableActions {
     a: Vec<Action>,
     state: AvailableActionState,
@@ -25,72 +42,63 @@ struct AvailableActions {
 
 impl AvailableActions {
     fn new() -> Self {
-        AvailableActions {
-            a: vec![
+        AvailableActions::at(AvailableActionState::FirstEligible)
+    }
+
+    fn at(state: AvailableActionState) -> Self {
+        let a: Vec<Action> = match state {
+            AvailableActionState::FirstEligible => vec![
                 Action::Pass,
                 Action::CommandOnly,
                 Action::CommandFeat,
                 Action::Event,
             ],
-            state: AvailableActionState::Start,
-        }
+            AvailableActionState::AfterCommandOnly => vec![Action::Pass, Action::LimitedCommand],
+            AvailableActionState::AfterCommandFeat => {
+                vec![Action::Pass, Action::LimitedCommand, Action::Event]
+            }
+            AvailableActionState::AfterEvent => {
+                vec![Action::Pass, Action::CommandOnly, Action::CommandFeat]
+            }
+            AvailableActionState::End => vec![],
+        };
+        AvailableActions { a, state }
     }
 
-    fn update_available_actions(self, selection: Option<Action>) -> AvailableActions {
-        match self.state {
-            AvailableActionState::Start => match selection.unwrap() {
-                Action::Pass => self,
-                Action::CommandOnly => AvailableActions {
-                    a: vec![Action::Pass, Action::LimitedCommand],
-                    state: AvailableActionState::A,
-                },
-                Action::CommandFeat => AvailableActions {
-                    a: vec![Action::Pass, Action::Event, Action::LimitedCommand],
-                    state: AvailableActionState::B,
-                },
-                Action::Event => AvailableActions {
-                    a: vec![Action::Pass, Action::CommandFeat],
-                    state: AvailableActionState::C,
-                },
-                _ => panic!("Invalid selected action for start"),
-            },
-            AvailableActionState::A => match selection.unwrap() {
-                Action::Pass => self,
-                Action::LimitedCommand => AvailableActions {
-                    a: vec![],
-                    state: AvailableActionState::End,
-                },
-                _ => panic!("Invalid selected action from Command Only"),
-            },
-            AvailableActionState::B => match selection.unwrap() {
-                Action::Pass => self,
-                Action::Event => AvailableActions {
-                    a: vec![],
-                    state: AvailableActionState::End,
-                },
-                Action::LimitedCommand => AvailableActions {
-                    a: vec![],
-                    state: AvailableActionState::End,
-                },
-                _ => panic!("Invalid selected action from Command + Feat"),
-            },
-            AvailableActionState::C => match selection.unwrap() {
-                Action::Pass => self,
-                Action::CommandFeat => AvailableActions {
-                    a: vec![],
-                    state: AvailableActionState::End,
-                },
-                _ => panic!("Invalid selected action from Event"),
-            },
-            AvailableActionState::End => {
-                panic!("We're finished with the round, just make a new AvailableActions")
-            }
+    fn update_available_actions(
+        self,
+        selection: Option<Action>,
+    ) -> Result<AvailableActions, SopError> {
+        let selection: Action = selection.ok_or(SopError::NoActionSelected)?;
+        if self.state == AvailableActionState::End {
+            return Err(SopError::CardAlreadyOver { action: selection });
+        }
+        if !self.a.contains(&selection) {
+            return Err(SopError::ActionNotAvailable {
+                action: selection,
+                available: self.a,
+            });
         }
+        let next: AvailableActions = match (self.state, selection) {
+            (_, Action::Pass) => self,
+            (AvailableActionState::FirstEligible, Action::CommandOnly) => {
+                AvailableActions::at(AvailableActionState::AfterCommandOnly)
+            }
+            (AvailableActionState::FirstEligible, Action::CommandFeat) => {
+                AvailableActions::at(AvailableActionState::AfterCommandFeat)
+            }
+            (AvailableActionState::FirstEligible, Action::Event) => {
+                AvailableActions::at(AvailableActionState::AfterEvent)
+            }
+            // Whatever the 2nd Eligible faction does ends the card
+            _ => AvailableActions::at(AvailableActionState::End),
+        };
+        return Ok(next);
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Action {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
     Pass,
     CommandOnly,
     LimitedCommand,
@@ -110,7 +118,7 @@ impl fmt::Display for Action {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PlayerState {
     Eligible,
     Passed,
@@ -118,7 +126,7 @@ pub enum PlayerState {
     Ineligible,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SequenceOfPlayState {
     CheckEndRound,
     CheckPlayerStatus,
@@ -126,19 +134,183 @@ pub enum SequenceOfPlayState {
     Acting,
     ResetEligibility,
     AdvanceEvents,
+    OfferPivotal,
+    ResolvingPivotal,
     Epoch,
+    GameOver,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameEnd {
+    FinalEpoch,
+    DeckExhausted,
+}
+
+// What the sequence of play is waiting on before it can take its next step
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NextInput {
+    // Nothing to ask, the step can just be taken
+    Automatic,
+    // A faction chooses its action, and the event side if it plays the event
+    Action(Player),
+    // These factions are offered their Pivotal events, in order
+    Pivotal(Vec<Player>),
+    // A faction carries out what it chose and may be asked about it
+    Resolving(Player),
+    GameOver,
+}
+
+// Something the sequence of play was asked to do that it cannot do from
+// where it is. Steps consume the sequence of play, so a caller that wants
+// to carry on keeps a copy. A command or event may also have changed the
+// board before failing, and the caller has to put that back too, as
+// Game::step does.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SopError {
+    WrongState {
+        step: &'static str,
+        expected: SequenceOfPlayState,
+        found: SequenceOfPlayState,
+    },
+    NoActionSelected,
+    ActionNotAvailable {
+        action: Action,
+        available: Vec<Action>,
+    },
+    CardAlreadyOver {
+        action: Action,
+    },
+    AnswerOutOfRange {
+        player: Player,
+        answer: usize,
+        answers: usize,
+    },
+    NoFactionToAct {
+        position: usize,
+    },
+    AlreadyActed {
+        player: Player,
+        state: PlayerState,
+    },
+    NoPivotalPlayer,
+    PivotalNotHeld(Player),
+    // The player asked to undo, redo or save instead of answering
+    Interrupted(Interrupt),
+    Command(CommandError),
+    // An event that can't be played, such as one not yet transcribed
+    Event(EventError),
+    // An event's text pointed at something that is not on the board
+    Board(BoardError),
+    PivotalInDeck {
+        number: u8,
+        name: String,
+    },
+}
+
+impl SopError {
+    fn out_of_range(player: Player, answer: usize, decision: &Decision) -> Self {
+        SopError::AnswerOutOfRange {
+            player,
+            answer,
+            answers: decision.answers(),
+        }
+    }
+
+    // Bad answers can simply be asked for again; anything else means the
+    // game itself has gone wrong
+    pub fn is_recoverable(&self) -> bool {
+        matches!(
+            self,
+            SopError::ActionNotAvailable { .. }
+                | SopError::AnswerOutOfRange { .. }
+                | SopError::Command(_)
+                | SopError::Event(_)
+        )
+    }
+}
+
+impl From<Interrupt> for SopError {
+    fn from(interrupt: Interrupt) -> Self {
+        SopError::Interrupted(interrupt)
+    }
 }
 
-#[derive(Clone)]
+impl fmt::Display for SopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SopError::WrongState {
+                step,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Can only {} in the {:?} state, currently in {:?}",
+                step, expected, found
+            ),
+            SopError::NoActionSelected => write!(f, "No action has been selected"),
+            SopError::ActionNotAvailable { action, available } => write!(
+                f,
+                "{} is not one of the available actions {:?}",
+                action, available
+            ),
+            SopError::CardAlreadyOver { action } => write!(
+                f,
+                "Both Eligible factions have acted, there is no room for {}",
+                action
+            ),
+            SopError::AnswerOutOfRange {
+                player,
+                answer,
+                answers,
+            } => write!(
+                f,
+                "{} answered {} but there are only {} options",
+                player, answer, answers
+            ),
+            SopError::NoFactionToAct { position } => write!(
+                f,
+                "There is no faction at position {} of the eligibility order",
+                position
+            ),
+            SopError::AlreadyActed { player, state } => {
+                write!(f, "{} is already at {:?} on this card", player, state)
+            }
+            SopError::NoPivotalPlayer => write!(f, "Nobody is playing a Pivotal event"),
+            SopError::Interrupted(interrupt) => write!(f, "Interrupted by {:?}", interrupt),
+            SopError::Command(e) => write!(f, "{}", e),
+            SopError::Event(e) => write!(f, "{}", e),
+            SopError::Board(e) => write!(f, "{}", e),
+            SopError::PivotalNotHeld(player) => {
+                write!(f, "{} no longer hold their Pivotal event", player)
+            }
+            SopError::PivotalInDeck { number, name } => write!(
+                f,
+                "Pivotal event {} ({}) was shuffled into the deck",
+                number, name
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SequenceOfPlay {
+    #[serde(serialize_with = "save::sorted")]
     player_eligibilities: HashMap<Player, PlayerState>,
     current_player: usize,
     pub state: SequenceOfPlayState,
     available_actions: AvailableActions,
     selected_action: Option<Action>,
+    selected_side: Option<Side>,
+    ineligible_next_card: Vec<Player>,
     event_deck: VecDeque<Event>,
     current_event: Event,
     event_discard: VecDeque<Event>,
+    #[serde(serialize_with = "save::sorted")]
+    held_pivotals: HashMap<Player, Event>,
+    played_pivotals: Vec<Player>,
+    pivotal_player: Option<Player>,
+    pivotal_in_play: Option<Event>,
+    end: Option<GameEnd>,
 }
 
 impl fmt::Display for SequenceOfPlay {
@@ -154,7 +326,7 @@ impl fmt::Display for SequenceOfPlay {
 // TODO: Track players in hashmap and vecs
 // TODO: Pretty print
 impl SequenceOfPlay {
-    pub fn new(mut events: VecDeque<Event>) -> Self {
+    pub fn new(mut events: VecDeque<Event>, pivotals: Vec<Event>) -> Self {
         let mut player_eligibilities: HashMap<Player, PlayerState> = HashMap::new();
         player_eligibilities.insert(Civitates, Eligible);
         player_eligibilities.insert(Dux, Eligible);
@@ -163,218 +335,1177 @@ impl SequenceOfPlay {
 
         let curr_event: Event = events.pop_front().unwrap();
         let discard: VecDeque<Event> = VecDeque::new();
+        let held_pivotals: HashMap<Player, Event> = pivotals
+            .into_iter()
+            .filter_map(|e| e.faction.map(|p| (p, e)))
+            .collect();
 
         SequenceOfPlay {
             player_eligibilities: player_eligibilities,
             current_player: 0,
-            state: SequenceOfPlayState::CheckEndRound,
+            state: SequenceOfPlayState::OfferPivotal,
             available_actions: AvailableActions::new(),
             selected_action: None,
+            selected_side: None,
+            ineligible_next_card: vec![],
             event_deck: events,
             current_event: curr_event,
             event_discard: discard,
+            held_pivotals,
+            played_pivotals: vec![],
+            pivotal_player: None,
+            pivotal_in_play: None,
+            end: None,
         }
     }
 
-    pub fn check_end_round(mut self) -> Self {
-        println!("Checking for end of round...");
-        match self.state {
-            SequenceOfPlayState::CheckEndRound => {
-                if self.current_player > 3
-                    || self.available_actions.state == AvailableActionState::End
-                {
-                    print!("Ending round");
-                    self.state = SequenceOfPlayState::ResetEligibility;
-                } else {
-                    println!("Continuing round");
-                    self.state = SequenceOfPlayState::CheckPlayerStatus;
-                }
-                return self;
+    fn expect_state(
+        &self,
+        step: &'static str,
+        expected: SequenceOfPlayState,
+    ) -> Result<(), SopError> {
+        if self.state != expected {
+            return Err(SopError::WrongState {
+                step,
+                expected,
+                found: self.state,
+            });
+        }
+        Ok(())
+    }
+
+    // The faction whose turn it is on the current card
+    fn current_faction(&self) -> Result<Player, SopError> {
+        self.current_event
+            .eligibility
+            .get(self.current_player)
+            .copied()
+            .ok_or(SopError::NoFactionToAct {
+                position: self.current_player,
+            })
+    }
+
+    pub fn check_end_round(mut self) -> Result<Self, SopError> {
+        narrate!("Checking for end of round...");
+        self.expect_state("check end round", SequenceOfPlayState::CheckEndRound)?;
+        if self.current_player > 3 || self.available_actions.state == AvailableActionState::End {
+            narrate!("Ending round");
+            self.state = SequenceOfPlayState::ResetEligibility;
+        } else {
+            narrate!("Continuing round");
+            self.state = SequenceOfPlayState::CheckPlayerStatus;
+        }
+        return Ok(self);
+    }
+
+    pub fn check_player_status(mut self) -> Result<Self, SopError> {
+        narrate!("Checking player state...");
+        self.expect_state(
+            "check player status",
+            SequenceOfPlayState::CheckPlayerStatus,
+        )?;
+        let player: Player = self.current_faction()?;
+        match self.player_eligibilities[&player] {
+            PlayerState::Eligible => {
+                narrate!("{} is eligible", player);
+                self.state = SequenceOfPlayState::ChoosingAction;
             }
-            _ => panic!(
-                "Can only check end round in CheckEndRound, currently in {:?}",
-                self.state
-            ),
+            PlayerState::Ineligible => {
+                narrate!("{} is ineligible, proceeding to next player", player);
+                self.current_player += 1;
+                self.state = SequenceOfPlayState::CheckEndRound;
+            }
+            // Only a Pivotal event lets a faction act out of turn
+            PlayerState::Acted if self.pivotal_player == Some(player) => {
+                narrate!(
+                    "{} already acted with a Pivotal event, proceeding to next player",
+                    player
+                );
+                self.current_player += 1;
+                self.state = SequenceOfPlayState::CheckEndRound;
+            }
+            state => return Err(SopError::AlreadyActed { player, state }),
         }
+        return Ok(self);
     }
 
-    pub fn check_player_status(mut self) -> Self {
-        println!("Checking player state...");
-        match self.state {
-            SequenceOfPlayState::CheckPlayerStatus => {
-                match self
-                    .player_eligibilities
-                    .get(&self.current_event.eligibility[self.current_player])
-                    .unwrap()
-                {
-                    PlayerState::Eligible => {
-                        println!(
-                            "{} is eligible",
-                            self.current_event.eligibility[self.current_player]
-                        );
-                        self.state = SequenceOfPlayState::ChoosingAction;
-                        return self;
-                    }
-                    PlayerState::Ineligible => {
-                        println!(
-                            "{} is ineligible, proceeding to next player",
-                            self.current_event.eligibility[self.current_player]
-                        );
-                        self.current_player += 1;
-                        self.state = SequenceOfPlayState::CheckEndRound;
-                        return self;
-                    }
-                    _ => panic!(
-                        "While checking player status found a player already at {:?}",
-                        self.player_eligibilities
-                            .get(&self.current_event.eligibility[self.current_player])
-                            .unwrap()
-                    ),
-                }
+    pub fn get_action(
+        mut self,
+        board: &Board,
+        decisions: &mut dyn DecisionProvider,
+    ) -> Result<Self, SopError> {
+        self.expect_state("get action", SequenceOfPlayState::ChoosingAction)?;
+        let player: Player = self.current_faction()?;
+        let options: Vec<Action> = self.offered_actions(player, board);
+        narrate!("Available actions: {:?}", options);
+        let view: DeckView = self.deck_view();
+        let options: &[Action] = &options;
+        let decision: Decision = Decision::Action { options };
+        let answer: usize = decisions.decide(player, &decision, Some(&view))?;
+        let selection: Action = *options
+            .get(answer)
+            .ok_or(SopError::out_of_range(player, answer, &decision))?;
+        narrate!("Selected {}", selection);
+        let side: Option<Side> = match selection {
+            Action::Event => {
+                let decision: Decision = Decision::Side {
+                    event: &self.current_event,
+                };
+                let answer: usize = decisions.decide(player, &decision, Some(&view))?;
+                Some(
+                    *SIDES
+                        .get(answer)
+                        .ok_or(SopError::out_of_range(player, answer, &decision))?,
+                )
+            }
+            _ => None,
+        };
+        self.selected_action = Some(selection);
+        self.selected_side = side;
+        self.state = SequenceOfPlayState::Acting;
+        return Ok(self);
+    }
+
+    // The actions the matrix allows that the faction could carry out: the
+    // Event only when the card can be played, and a Command or Feat only
+    // when the faction has one
+    fn offered_actions(&self, player: Player, board: &Board) -> Vec<Action> {
+        let event: bool = effects::transcribed(&self.current_event);
+        let command: bool = !commands_for(player, board).is_empty();
+        let feat: bool = command && !feats_for(player, board).is_empty();
+        return self
+            .available_actions
+            .a
+            .iter()
+            .copied()
+            .filter(|a| match a {
+                Action::Pass => true,
+                Action::Event => event,
+                Action::CommandOnly | Action::LimitedCommand => command,
+                Action::CommandFeat => feat,
+            })
+            .collect();
+    }
+
+    pub fn acting(
+        mut self,
+        board: &mut Board,
+        dice: &mut Dice,
+        decisions: &mut dyn DecisionProvider,
+    ) -> Result<Self, SopError> {
+        self.expect_state("act", SequenceOfPlayState::Acting)?;
+        let player: Player = self.current_faction()?;
+        let action: Action = self.selected_action.ok_or(SopError::NoActionSelected)?;
+        // Checked before anything changes so a bad choice can be made again
+        let available: AvailableActions = self
+            .available_actions
+            .clone()
+            .update_available_actions(Some(action))?;
+        narrate!("{} performing action: {:?}", player, action);
+        match action {
+            Action::Pass => {
+                self.player_eligibilities
+                    .insert(player, PlayerState::Passed);
+            }
+            Action::Event => {
+                self.player_eligibilities.insert(player, PlayerState::Acted);
+                self.play_event(board, dice, decisions)?;
+            }
+            Action::CommandOnly | Action::CommandFeat | Action::LimitedCommand => {
+                self.player_eligibilities.insert(player, PlayerState::Acted);
+                self.command_and_feat(player, action, board, dice, decisions)?;
             }
-            _ => panic!(
-                "Can only check player status in CheckPlayerStatus, currently in {:?}",
-                self.state
-            ),
         }
+        self.state = SequenceOfPlayState::CheckEndRound;
+        self.available_actions = available;
+        self.current_player += 1;
+        return Ok(self);
     }
 
-    // TODO: dependency inject query to handle user input vs bot input (vs automated testing input)?
-    pub fn get_action(mut self) -> Self {
-        match self.state {
-            SequenceOfPlayState::ChoosingAction => {
-                println!("Available actions: {:?}", self.available_actions.a);
-                println!(
-                    "\nGetting first action from {}",
-                    self.current_event.eligibility[self.current_player],
-                );
-                let selection: Action = self.available_actions.a[Select::new()
-                    .with_prompt(format!("Select one of the following actions!"))
-                    .items(&self.available_actions.a)
-                    .interact()
-                    .unwrap()];
-                println!("Selected {}", selection);
-                self.selected_action = Some(selection);
-                self.state = SequenceOfPlayState::Acting;
-                return self;
+    // The faction picks a Command and, for Command + Feat, a Feat to go with
+    // it. Only a Limited Command is held to one space, and it has no Feat.
+    fn command_and_feat(
+        &self,
+        player: Player,
+        action: Action,
+        board: &mut Board,
+        dice: &mut Dice,
+        decisions: &mut dyn DecisionProvider,
+    ) -> Result<(), SopError> {
+        let limited: bool = action == Action::LimitedCommand;
+        let view: DeckView = self.deck_view();
+        let commands: Vec<CommandType> = commands_for(player, board);
+        let Some(command) = choose(player, "Which command?", &commands, decisions, &view)? else {
+            narrate!("{} have no command they can do", player);
+            return Ok(());
+        };
+        let mut decisions: ShowingCards = ShowingCards {
+            decisions,
+            view: &view,
+        };
+        commands::execute(player, command, board, dice, &mut decisions, limited, false)
+            .map_err(|e| command_error(player, e))?;
+        if action != Action::CommandFeat {
+            return Ok(());
+        }
+        let feats: Vec<FeatType> = feats_for(player, board);
+        let Some(feat) = choose(player, "Which feat?", &feats, &mut decisions, &view)? else {
+            narrate!("{} have no feat they can do", player);
+            return Ok(());
+        };
+        commands::feat(player, feat, board, &mut decisions, limited)
+            .map_err(|e| command_error(player, e))?;
+        return Ok(());
+    }
+
+    fn play_event(
+        &mut self,
+        board: &mut Board,
+        dice: &mut Dice,
+        decisions: &mut dyn DecisionProvider,
+    ) -> Result<(), SopError> {
+        let side: Side = self.selected_side.unwrap_or(Side::Unshaded);
+        let player: Player = self.current_event.eligibility[self.current_player];
+        narrate!("Playing {} ({})", self.current_event.name, side);
+        let outcome: EventOutcome = resolve(
+            &self.current_event,
+            side,
+            player,
+            board,
+            decisions,
+            &self.deck_view(),
+        )?;
+        self.apply_outcome(outcome, board, dice, decisions)?;
+        return Ok(());
+    }
+
+    // Free Commands and Feats an event grants are carried out straight
+    // away, or declined, by the faction they were granted to
+    fn apply_outcome(
+        &mut self,
+        outcome: EventOutcome,
+        board: &mut Board,
+        dice: &mut Dice,
+        decisions: &mut dyn DecisionProvider,
+    ) -> Result<(), SopError> {
+        let view: DeckView = self.deck_view();
+        let mut decisions: ShowingCards = ShowingCards {
+            decisions,
+            view: &view,
+        };
+        for (player, command) in outcome.free_commands {
+            let prompt: String = format!("{} may {} free", player, command);
+            if take_free(player, &prompt, &mut decisions, &view)? {
+                commands::execute(player, command, board, dice, &mut decisions, false, true)
+                    .map_err(|e| command_error(player, e))?;
+            }
+        }
+        for (player, feat) in outcome.free_feats {
+            let prompt: String = format!("{} may {} free", player, feat);
+            if take_free(player, &prompt, &mut decisions, &view)? {
+                commands::feat(player, feat, board, &mut decisions, false)
+                    .map_err(|e| command_error(player, e))?;
             }
-            _ => panic!(
-                "Can only get action in GettingAction state, currently in {:?}",
-                self.state
-            ),
         }
+        self.ineligible_next_card
+            .extend(outcome.ineligible_next_card);
+        return Ok(());
     }
 
-    pub fn acting(mut self) -> Self {
-        match self.state {
-            SequenceOfPlayState::Acting => {
-                println!(
-                    "{} performing action: {:?}",
-                    self.current_event.eligibility[self.current_player],
-                    self.selected_action.unwrap()
-                );
-                match self.selected_action.unwrap() {
-                    Action::Pass => {
-                        self.player_eligibilities.insert(
-                            self.current_event.eligibility[self.current_player],
-                            PlayerState::Passed,
-                        );
-                    }
-                    _ => {
-                        self.player_eligibilities.insert(
-                            self.current_event.eligibility[self.current_player],
-                            PlayerState::Acted,
-                        );
-                    }
-                }
-                self.state = SequenceOfPlayState::CheckEndRound;
-                self.available_actions = self
-                    .available_actions
-                    .update_available_actions(self.selected_action);
-                self.current_player += 1;
-                return self;
+    pub fn reset_eligibility(mut self) -> Result<Self, SopError> {
+        narrate!("Reseting eligibility...");
+        self.expect_state("reset eligibility", SequenceOfPlayState::ResetEligibility)?;
+        let mut new_eligibility: HashMap<Player, PlayerState> = HashMap::new();
+        for (elig, p) in &self.player_eligibilities {
+            match *p {
+                PlayerState::Eligible => new_eligibility.insert(*elig, PlayerState::Eligible),
+                PlayerState::Ineligible => new_eligibility.insert(*elig, PlayerState::Eligible),
+                PlayerState::Passed => new_eligibility.insert(*elig, PlayerState::Eligible),
+                PlayerState::Acted => new_eligibility.insert(*elig, PlayerState::Ineligible),
+            };
+        }
+        for player in self.ineligible_next_card.drain(..) {
+            narrate!("{} Ineligible through the next card", player);
+            new_eligibility.insert(player, PlayerState::Ineligible);
+        }
+        self.player_eligibilities = new_eligibility;
+        self.available_actions = AvailableActions::new();
+        self.state = SequenceOfPlayState::AdvanceEvents;
+        narrate!("Eligibilities reset");
+        return Ok(self);
+    }
+
+    pub fn advance_events(mut self, board: &mut Board) -> Result<Self, SopError> {
+        narrate!("Advancing events...");
+        self.expect_state("advance events", SequenceOfPlayState::AdvanceEvents)?;
+        if let Some(event) = self
+            .event_deck
+            .front()
+            .filter(|e| e.event_type == EventType::Pivotal)
+        {
+            return Err(SopError::PivotalInDeck {
+                number: event.number,
+                name: event.name.clone(),
+            });
+        }
+        report_expired(board.expire_lasting(Boundary::EndOfCard));
+        // With nothing left to reveal the last card stays face up
+        let Some(next) = self.event_deck.pop_front() else {
+            narrate!("The event deck is exhausted");
+            self.end = Some(GameEnd::DeckExhausted);
+            self.state = SequenceOfPlayState::GameOver;
+            return Ok(self);
+        };
+        let played: Event = std::mem::replace(&mut self.current_event, next);
+        self.event_discard.push_front(played);
+        if let Some(pivotal) = self.pivotal_in_play.take() {
+            self.event_discard.push_front(pivotal);
+        }
+        self.pivotal_player = None;
+        self.current_player = 0;
+        self.state = match self.current_event.event_type {
+            EventType::Epoch => SequenceOfPlayState::Epoch,
+            _ => SequenceOfPlayState::OfferPivotal,
+        };
+        narrate!("Events advanced\n\n");
+        narrate!("{}", self.deck_view().summary());
+        narrate!("{}", self);
+        return Ok(self);
+    }
+
+    // Factions whose Pivotal event could pre-empt the current card. That is
+    // only possible before anyone has acted on it, by a faction that is
+    // Eligible, and not while an Epoch is coming up next.
+    pub fn playable_pivotals(&self, board: &Board) -> Vec<Player> {
+        let untouched: bool = self.current_player == 0
+            && self.available_actions.state == AvailableActionState::FirstEligible;
+        let epoch_next: bool = self
+            .event_deck
+            .front()
+            .is_some_and(|e| e.event_type == EventType::Epoch);
+        if !untouched || epoch_next || self.current_event.event_type != EventType::Standard {
+            return vec![];
+        }
+        return self
+            .current_event
+            .eligibility
+            .iter()
+            .filter(|p| {
+                matches!(
+                    self.player_eligibilities.get(p),
+                    Some(PlayerState::Eligible)
+                )
+            })
+            .filter(|p| {
+                self.held_pivotals.get(p).is_some_and(|e| {
+                    effects::transcribed(e) && effects::pivotal_precondition(e, board)
+                })
+            })
+            .copied()
+            .collect();
+    }
+
+    // Factions are asked in the current card's eligibility order and the
+    // first to accept plays
+    pub fn offer_pivotal(
+        mut self,
+        board: &Board,
+        decisions: &mut dyn DecisionProvider,
+    ) -> Result<Self, SopError> {
+        self.expect_state("offer Pivotal events", SequenceOfPlayState::OfferPivotal)?;
+        for player in self.playable_pivotals(board) {
+            let event: &Event = &self.held_pivotals[&player];
+            let play: bool = decisions.decide(
+                player,
+                &Decision::Pivotal { event },
+                Some(&self.deck_view()),
+            )? == 1;
+            if play {
+                self.pivotal_player = Some(player);
+                self.state = SequenceOfPlayState::ResolvingPivotal;
+                return Ok(self);
             }
-            _ => panic!(
-                "Can only do action in Acting state, currently in {:?} state",
-                self.state
-            ),
         }
+        self.state = SequenceOfPlayState::CheckEndRound;
+        return Ok(self);
     }
 
-    pub fn reset_eligibility(mut self) -> Self {
-        println!("Reseting eligibility...");
+    // The Pivotal event takes the place of the current card's event and
+    // counts as the first eligible faction's action
+    pub fn resolve_pivotal(
+        mut self,
+        board: &mut Board,
+        dice: &mut Dice,
+        decisions: &mut dyn DecisionProvider,
+    ) -> Result<Self, SopError> {
+        self.expect_state(
+            "resolve a Pivotal event",
+            SequenceOfPlayState::ResolvingPivotal,
+        )?;
+        let player: Player = self.pivotal_player.ok_or(SopError::NoPivotalPlayer)?;
+        let available: AvailableActions = self
+            .available_actions
+            .clone()
+            .update_available_actions(Some(Action::Event))?;
+        let pivotal: Event = self
+            .held_pivotals
+            .remove(&player)
+            .ok_or(SopError::PivotalNotHeld(player))?;
+        narrate!(
+            "{} plays {}, pre-empting {}",
+            player,
+            pivotal.name,
+            self.current_event.name
+        );
+        let outcome: EventOutcome = resolve(
+            &pivotal,
+            Side::Unshaded,
+            player,
+            board,
+            decisions,
+            &self.deck_view(),
+        )?;
+        self.apply_outcome(outcome, board, dice, decisions)?;
+        self.player_eligibilities.insert(player, PlayerState::Acted);
+        self.played_pivotals.push(player);
+        self.pivotal_in_play = Some(pivotal);
+        self.available_actions = available;
+        self.state = SequenceOfPlayState::CheckEndRound;
+        return Ok(self);
+    }
+
+    pub fn next_input(&self, board: &Board) -> NextInput {
         match self.state {
-            SequenceOfPlayState::ResetEligibility => {
-                let mut new_eligibility: HashMap<Player, PlayerState> = HashMap::new();
-                for (elig, p) in &self.player_eligibilities {
-                    match *p {
-                        PlayerState::Eligible => {
-                            new_eligibility.insert(*elig, PlayerState::Eligible)
-                        }
-                        PlayerState::Ineligible => {
-                            new_eligibility.insert(*elig, PlayerState::Eligible)
-                        }
-                        PlayerState::Passed => new_eligibility.insert(*elig, PlayerState::Eligible),
-                        PlayerState::Acted => {
-                            new_eligibility.insert(*elig, PlayerState::Ineligible)
-                        }
-                    };
+            SequenceOfPlayState::ChoosingAction => match self.current_faction() {
+                Ok(player) => NextInput::Action(player),
+                Err(_) => NextInput::Automatic,
+            },
+            SequenceOfPlayState::Acting => match (self.selected_action, self.current_faction()) {
+                (Some(Action::Pass), _) | (None, _) | (_, Err(_)) => NextInput::Automatic,
+                (Some(_), Ok(player)) => NextInput::Resolving(player),
+            },
+            SequenceOfPlayState::OfferPivotal => {
+                let players: Vec<Player> = self.playable_pivotals(board);
+                if players.is_empty() {
+                    NextInput::Automatic
+                } else {
+                    NextInput::Pivotal(players)
                 }
-                self.player_eligibilities = new_eligibility;
-                self.available_actions = AvailableActions::new();
-                self.state = SequenceOfPlayState::AdvanceEvents;
-                println!("Eligibilities reset");
-                return self;
-            }
-            _ => {
-                panic!(
-                    "Can only do cleanup in Reseting state, currently in {:?} state",
-                    self.state
-                );
             }
+            SequenceOfPlayState::ResolvingPivotal => match self.pivotal_player {
+                Some(player) => NextInput::Resolving(player),
+                None => NextInput::Automatic,
+            },
+            SequenceOfPlayState::GameOver => NextInput::GameOver,
+            _ => NextInput::Automatic,
         }
     }
 
-    pub fn advance_events(mut self) -> Self {
-        println!("Advancing events...");
-        match self.state {
-            SequenceOfPlayState::AdvanceEvents => {
-                self.event_discard.push_front(self.current_event);
-                self.current_event = self.event_deck.pop_front().unwrap();
-                self.current_player = 0;
-                match self.event_deck[0].event_type {
-                    EventType::Standard => self.state = SequenceOfPlayState::ChoosingAction,
-                    EventType::Epoch => {
-                        self.state = SequenceOfPlayState::Epoch;
-                        let epoch: Event = self.event_deck.pop_front().unwrap();
-                        self.event_deck.push_front(self.current_event);
-                        self.current_event = epoch;
+    // After a bad answer the faction goes back to choosing its action
+    pub fn ask_again(mut self) -> Self {
+        if self.state == SequenceOfPlayState::Acting {
+            self.selected_action = None;
+            self.selected_side = None;
+            self.state = SequenceOfPlayState::ChoosingAction;
+        }
+        return self;
+    }
+
+    pub fn epoch(mut self, board: &mut Board) -> Result<Self, SopError> {
+        narrate!("Begin Epoch round");
+        self.expect_state("do the Epoch round", SequenceOfPlayState::Epoch)?;
+        report_expired(board.expire_lasting(Boundary::Epoch));
+        for state in self.player_eligibilities.values_mut() {
+            *state = PlayerState::Eligible;
+        }
+        self.ineligible_next_card.clear();
+        if self.next_epoch_in_deck().is_none() {
+            narrate!("That was the final Epoch");
+            self.end = Some(GameEnd::FinalEpoch);
+            self.state = SequenceOfPlayState::GameOver;
+        } else {
+            self.state = SequenceOfPlayState::AdvanceEvents;
+        }
+        return Ok(self);
+    }
+}
+
+impl SequenceOfPlay {
+    // Cards still to be revealed before the next Epoch, or None when no
+    // Epoch is left in the deck
+    pub fn cards_until_epoch(&self) -> Option<usize> {
+        if self.current_event.event_type == EventType::Epoch {
+            return Some(0);
+        }
+        self.next_epoch_in_deck()
+    }
+
+    fn next_epoch_in_deck(&self) -> Option<usize> {
+        self.event_deck
+            .iter()
+            .position(|e| e.event_type == EventType::Epoch)
+    }
+
+    // Every card the sequence of play holds, wherever it is
+    pub fn card_numbers(&self) -> Vec<u8> {
+        let mut numbers: Vec<u8> = vec![self.current_event.number];
+        numbers.extend(self.event_deck.iter().map(|e| e.number));
+        numbers.extend(self.event_discard.iter().map(|e| e.number));
+        numbers.extend(self.held_pivotals.values().map(|e| e.number));
+        numbers.extend(self.pivotal_in_play.iter().map(|e| e.number));
+        return numbers;
+    }
+
+    // Whoever is up on the current card, if anyone
+    pub fn faction_to_act(&self) -> Option<Player> {
+        self.current_faction().ok()
+    }
+
+    pub fn selected(&self) -> (Option<Action>, Option<Side>) {
+        (self.selected_action, self.selected_side)
+    }
+
+    pub fn eligibility(&self, player: Player) -> Option<PlayerState> {
+        self.player_eligibilities.get(&player).copied()
+    }
+
+    pub fn pivotal_player(&self) -> Option<Player> {
+        self.pivotal_player
+    }
+
+    // Cards still face down in the deck
+    pub fn cards_left(&self) -> usize {
+        self.event_deck.len()
+    }
+
+    pub fn end(&self) -> Option<GameEnd> {
+        self.end
+    }
+
+    pub fn deck_view(&self) -> DeckView<'_> {
+        DeckView {
+            current: &self.current_event,
+            pivotal_in_play: self.pivotal_in_play.as_ref(),
+            upcoming: self.event_deck.front(),
+            cards_until_epoch: self.cards_until_epoch(),
+            discard: self.event_discard.iter().collect(),
+        }
+    }
+}
+
+// Board errors are reported and the event does what it could; anything that
+// stopped the choices being made is passed on
+fn resolve(
+    event: &Event,
+    side: Side,
+    player: Player,
+    board: &mut Board,
+    decisions: &mut dyn DecisionProvider,
+    view: &DeckView,
+) -> Result<EventOutcome, SopError> {
+    let mut choose = |prompt: &str, options: &[String]| {
+        decisions.decide(player, &Decision::Choice { prompt, options }, Some(view))
+    };
+    match effects::resolve_event(event, side, board, &mut choose) {
+        Ok(outcome) => Ok(outcome),
+        Err(EventError::Board(e)) => Err(SopError::Board(e)),
+        Err(EventError::Interrupted(interrupt)) => Err(SopError::Interrupted(interrupt)),
+        Err(EventError::AnswerOutOfRange { answer, answers }) => Err(SopError::AnswerOutOfRange {
+            player,
+            answer,
+            answers,
+        }),
+        Err(e @ EventError::NotImplemented { .. }) => Err(SopError::Event(e)),
+    }
+}
+
+// The commands a faction could pick now: written, and not blocked by a
+// Capability or Momentum
+fn commands_for(player: Player, board: &Board) -> Vec<CommandType> {
+    CommandType::implemented(player)
+        .into_iter()
+        .filter(|c| !board.lasting().command_blocked(player, *c))
+        .collect()
+}
+
+fn feats_for(player: Player, board: &Board) -> Vec<FeatType> {
+    FeatType::implemented(player)
+        .into_iter()
+        .filter(|f| !board.lasting().feat_blocked(player, *f))
+        .collect()
+}
+
+// Whether a faction takes up a free Command or Feat
+fn take_free(
+    player: Player,
+    prompt: &str,
+    decisions: &mut dyn DecisionProvider,
+    view: &DeckView,
+) -> Result<bool, SopError> {
+    let options: [String; 2] = [String::from("Take it"), String::from("Decline")];
+    let decision: Decision = Decision::Choice {
+        prompt,
+        options: &options,
+    };
+    match decisions.decide(player, &decision, Some(view))? {
+        0 => Ok(true),
+        1 => {
+            narrate!("{} declined", player);
+            Ok(false)
+        }
+        answer => Err(SopError::out_of_range(player, answer, &decision)),
+    }
+}
+
+// One of `options`, or None when there are none to pick from
+fn choose<T: Copy + fmt::Display>(
+    player: Player,
+    prompt: &str,
+    options: &[T],
+    decisions: &mut dyn DecisionProvider,
+    view: &DeckView,
+) -> Result<Option<T>, SopError> {
+    if options.is_empty() {
+        return Ok(None);
+    }
+    let labels: Vec<String> = options.iter().map(|o| o.to_string()).collect();
+    let decision: Decision = Decision::Choice {
+        prompt,
+        options: &labels,
+    };
+    let answer: usize = decisions.decide(player, &decision, Some(view))?;
+    match options.get(answer) {
+        Some(option) => Ok(Some(*option)),
+        None => Err(SopError::out_of_range(player, answer, &decision)),
+    }
+}
+
+fn command_error(player: Player, e: CommandError) -> SopError {
+    match e {
+        CommandError::Interrupted(interrupt) => SopError::Interrupted(interrupt),
+        CommandError::AnswerOutOfRange { answer, answers } => SopError::AnswerOutOfRange {
+            player,
+            answer,
+            answers,
+        },
+        e => SopError::Command(e),
+    }
+}
+
+fn report_expired(expired: Vec<LastingEffect>) {
+    for effect in expired {
+        narrate!("{} is no longer in effect", effect.name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::super::board::{Track, setup_etty_tyrants};
+    use super::super::concepts::UnitClass;
+    use super::super::decisions::{Bot, Scripted};
+    use super::super::events::load_catalogue;
+    use super::super::setup::Scenario;
+    use super::*;
+
+    fn new_sop() -> SequenceOfPlay {
+        let (pivotals, cards): (Vec<Event>, Vec<Event>) = load_catalogue()
+            .unwrap()
+            .into_iter()
+            .partition(|e| e.event_type == EventType::Pivotal);
+        SequenceOfPlay::new(
+            Scenario::EttyTyrants
+                .deck(cards, &mut ChaCha8Rng::seed_from_u64(0))
+                .unwrap(),
+            pivotals,
+        )
+    }
+
+    // None of the Pivotal events are in the catalogue yet, so the tests
+    // deal one out themselves
+    fn pivotal(player: Player) -> Event {
+        Event {
+            number: 99,
+            name: format!("{} Pivotal", player),
+            eligibility: vec![],
+            unshaded: String::new(),
+            shaded: String::new(),
+            historical_notes: String::new(),
+            event_type: EventType::Pivotal,
+            faction: Some(player),
+        }
+    }
+
+    #[test]
+    fn test_untranscribed_pivotals_are_not_offered() {
+        let mut board: Board = setup_etty_tyrants();
+        let mut sop: SequenceOfPlay = new_sop();
+        sop.held_pivotals.insert(Saxons, pivotal(Saxons));
+        assert!(sop.playable_pivotals(&board).is_empty());
+
+        // Nobody is asked and the card goes on as normal
+        let offered: SequenceOfPlay = sop
+            .clone()
+            .offer_pivotal(&board, &mut Scripted::new(vec![]))
+            .unwrap();
+        assert_eq!(offered.state, SequenceOfPlayState::CheckEndRound);
+
+        // Forced through anyway, the card is refused and stays in hand
+        sop.pivotal_player = Some(Saxons);
+        sop.state = SequenceOfPlayState::ResolvingPivotal;
+        let e = sop
+            .clone()
+            .resolve_pivotal(
+                &mut board,
+                &mut Dice::new(&mut ChaCha8Rng::seed_from_u64(0)),
+                &mut Scripted::new(vec![]),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            e,
+            SopError::Event(EventError::NotImplemented { .. })
+        ));
+        assert!(sop.held_pivotals.contains_key(&Saxons));
+    }
+
+    #[test]
+    fn test_deck_view() {
+        let mut board: Board = setup_etty_tyrants();
+        let mut sop: SequenceOfPlay = new_sop();
+        let first: u8 = sop.current_event.number;
+        let upcoming: u8 = sop.event_deck[0].number;
+        let until_epoch: usize = sop.cards_until_epoch().unwrap();
+        assert_eq!(sop.event_deck[until_epoch].event_type, EventType::Epoch);
+
+        let view: DeckView = sop.deck_view();
+        assert_eq!(view.current.number, first);
+        assert_eq!(view.upcoming.unwrap().number, upcoming);
+        assert!(view.discard.is_empty());
+
+        sop.state = SequenceOfPlayState::AdvanceEvents;
+        sop = sop.advance_events(&mut board).unwrap();
+        assert_eq!(sop.cards_until_epoch(), Some(until_epoch - 1));
+        let view: DeckView = sop.deck_view();
+        assert_eq!(view.current.number, upcoming);
+        assert_eq!(
+            view.discard.iter().map(|e| e.number).collect::<Vec<u8>>(),
+            vec![first]
+        );
+        assert!(
+            view.summary()
+                .contains(&format!("{} cards before", until_epoch - 1))
+        );
+    }
+
+    // A hand-stacked deck: two cards, an Epoch, a card, the final Epoch, a
+    // card that is never reached
+    fn stacked_sop() -> SequenceOfPlay {
+        let (pivotals, cards): (Vec<Event>, Vec<Event>) = load_catalogue()
+            .unwrap()
+            .into_iter()
+            .partition(|e| e.event_type == EventType::Pivotal);
+        let (epochs, standard): (Vec<Event>, Vec<Event>) = cards
+            .into_iter()
+            .partition(|e| e.event_type == EventType::Epoch);
+        let deck: VecDeque<Event> = VecDeque::from(vec![
+            standard[0].clone(),
+            standard[1].clone(),
+            epochs[0].clone(),
+            standard[2].clone(),
+            epochs[0].clone(),
+            standard[3].clone(),
+        ]);
+        SequenceOfPlay::new(deck, pivotals)
+    }
+
+    fn advance(sop: SequenceOfPlay, board: &mut Board) -> SequenceOfPlay {
+        let mut sop: SequenceOfPlay = sop;
+        sop.state = SequenceOfPlayState::AdvanceEvents;
+        return sop.advance_events(board).unwrap();
+    }
+
+    #[test]
+    fn test_play_through_every_epoch() {
+        let mut board: Board = setup_etty_tyrants();
+        let mut sop: SequenceOfPlay = stacked_sop();
+
+        // The Epoch is acted on when it is revealed, not a card early
+        sop = advance(sop, &mut board);
+        assert_eq!(sop.state, SequenceOfPlayState::OfferPivotal);
+        sop = advance(sop, &mut board);
+        assert_eq!(sop.state, SequenceOfPlayState::Epoch);
+        assert_eq!(sop.current_event.event_type, EventType::Epoch);
+
+        sop.player_eligibilities
+            .insert(Dux, PlayerState::Ineligible);
+        sop = sop.epoch(&mut board).unwrap();
+        assert_eq!(sop.state, SequenceOfPlayState::AdvanceEvents);
+        assert!(matches!(sop.player_eligibilities[&Dux], Eligible));
+
+        sop = advance(sop, &mut board);
+        assert_eq!(sop.state, SequenceOfPlayState::OfferPivotal);
+        sop = advance(sop, &mut board);
+        assert_eq!(sop.state, SequenceOfPlayState::Epoch);
+        sop = sop.epoch(&mut board).unwrap();
+        assert_eq!(sop.state, SequenceOfPlayState::GameOver);
+        assert_eq!(sop.end(), Some(GameEnd::FinalEpoch));
+        assert_eq!(sop.event_deck.len(), 1);
+    }
+
+    #[test]
+    fn test_deck_exhaustion_ends_the_game() {
+        let mut board: Board = setup_etty_tyrants();
+        let mut sop: SequenceOfPlay = stacked_sop();
+        sop.event_deck
+            .retain(|e| e.event_type == EventType::Standard);
+        let last: u8 = sop.event_deck.back().unwrap().number;
+        while sop.state != SequenceOfPlayState::GameOver {
+            sop = advance(sop, &mut board);
+        }
+        assert_eq!(sop.end(), Some(GameEnd::DeckExhausted));
+        assert_eq!(sop.current_event.number, last);
+    }
+
+    use AvailableActionState::{
+        AfterCommandFeat, AfterCommandOnly, AfterEvent, End, FirstEligible,
+    };
+
+    const ALL_ACTIONS: [Action; 5] = [
+        Action::Pass,
+        Action::CommandOnly,
+        Action::LimitedCommand,
+        Action::CommandFeat,
+        Action::Event,
+    ];
+
+    // Every legal (state, action) pair and where it leads
+    const TRANSITIONS: [(AvailableActionState, Action, AvailableActionState); 12] = [
+        (FirstEligible, Action::Pass, FirstEligible),
+        (FirstEligible, Action::CommandOnly, AfterCommandOnly),
+        (FirstEligible, Action::CommandFeat, AfterCommandFeat),
+        (FirstEligible, Action::Event, AfterEvent),
+        (AfterCommandOnly, Action::Pass, AfterCommandOnly),
+        (AfterCommandOnly, Action::LimitedCommand, End),
+        (AfterCommandFeat, Action::Pass, AfterCommandFeat),
+        (AfterCommandFeat, Action::LimitedCommand, End),
+        (AfterCommandFeat, Action::Event, End),
+        (AfterEvent, Action::Pass, AfterEvent),
+        (AfterEvent, Action::CommandOnly, End),
+        (AfterEvent, Action::CommandFeat, End),
+    ];
+
+    #[test]
+    fn test_option_matrix() {
+        for state in [
+            FirstEligible,
+            AfterCommandOnly,
+            AfterCommandFeat,
+            AfterEvent,
+            End,
+        ] {
+            for action in ALL_ACTIONS {
+                let expected = TRANSITIONS
+                    .iter()
+                    .find(|(from, a, _)| *from == state && *a == action);
+                let available: AvailableActions = AvailableActions::at(state);
+                assert_eq!(
+                    available.a.contains(&action),
+                    expected.is_some(),
+                    "{:?} in {:?}",
+                    action,
+                    state
+                );
+                let result = available.update_available_actions(Some(action));
+                match expected {
+                    Some((_, _, next)) => {
+                        assert_eq!(result.unwrap().state, *next, "{:?} in {:?}", action, state)
                     }
-                    EventType::Pivotal => {
-                        panic!("How did a Pivotal get to be mixed into the deck???")
+                    // Once both have acted nothing more fits on the card
+                    None if state == End => {
+                        assert_eq!(result.unwrap_err(), SopError::CardAlreadyOver { action })
                     }
+                    None => assert!(
+                        matches!(result, Err(SopError::ActionNotAvailable { .. })),
+                        "{:?} in {:?}",
+                        action,
+                        state
+                    ),
                 }
-                println!("Events advanced\n\n");
-                println!("{}", self);
-                return self;
-            }
-            _ => {
-                panic!(
-                    "Can only advance cards in AdvanceEvents state, currently in {:?} state",
-                    self.state
-                );
             }
         }
     }
 
-    pub fn epoch(mut self) -> Self {
-        println!("Begin Epoch round");
-        match self.state {
-            SequenceOfPlayState::Epoch => {
-                self.state = SequenceOfPlayState::AdvanceEvents;
-                return self;
+    #[test]
+    fn test_invalid_selections_are_errors() {
+        let refused = AvailableActions::at(AfterCommandOnly)
+            .update_available_actions(Some(Action::CommandFeat))
+            .unwrap_err();
+        assert!(matches!(refused, SopError::ActionNotAvailable { .. }));
+        assert!(refused.is_recoverable());
+        assert_eq!(
+            AvailableActions::at(End)
+                .update_available_actions(Some(Action::Pass))
+                .unwrap_err(),
+            SopError::CardAlreadyOver {
+                action: Action::Pass
             }
-            _ => panic!("Attempting to do epoch round while in {:?}", self.state),
+        );
+        assert_eq!(
+            AvailableActions::new()
+                .update_available_actions(None)
+                .unwrap_err(),
+            SopError::NoActionSelected
+        );
+    }
+
+    #[test]
+    fn test_transitions_refuse_the_wrong_state() {
+        let mut board: Board = setup_etty_tyrants();
+        let sop: SequenceOfPlay = new_sop();
+        let e = sop.clone().check_end_round().unwrap_err();
+        assert_eq!(
+            e,
+            SopError::WrongState {
+                step: "check end round",
+                expected: SequenceOfPlayState::CheckEndRound,
+                found: SequenceOfPlayState::OfferPivotal,
+            }
+        );
+        assert!(!e.is_recoverable());
+        assert!(sop.clone().check_player_status().is_err());
+        assert!(sop.clone().advance_events(&mut board).is_err());
+        assert!(sop.clone().epoch(&mut board).is_err());
+        assert!(
+            sop.clone()
+                .acting(
+                    &mut board,
+                    &mut Dice::new(&mut ChaCha8Rng::seed_from_u64(0)),
+                    &mut Scripted::new(vec![])
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_bad_answers_can_be_asked_again() {
+        let mut board: Board = setup_etty_tyrants();
+        let mut sop: SequenceOfPlay = new_sop();
+        sop.state = SequenceOfPlayState::ChoosingAction;
+        let e = sop
+            .clone()
+            .get_action(&board, &mut Scripted::new(vec![9]))
+            .unwrap_err();
+        assert!(matches!(e, SopError::AnswerOutOfRange { answer: 9, .. }));
+        assert!(e.is_recoverable());
+
+        // An action that is not on offer leaves the board alone
+        sop.state = SequenceOfPlayState::Acting;
+        sop.selected_action = Some(Action::LimitedCommand);
+        let e = sop
+            .clone()
+            .acting(
+                &mut board,
+                &mut Dice::new(&mut ChaCha8Rng::seed_from_u64(0)),
+                &mut Scripted::new(vec![]),
+            )
+            .unwrap_err();
+        assert!(e.is_recoverable());
+        sop = sop.ask_again();
+        assert_eq!(sop.state, SequenceOfPlayState::ChoosingAction);
+        assert!(sop.selected_action.is_none());
+    }
+
+    #[test]
+    fn test_free_commands_from_events_are_carried_out() {
+        let mut board: Board = setup_etty_tyrants();
+        let mut sop: SequenceOfPlay = new_sop();
+        let resources: u8 = board.track(Track::DuxResources);
+        let outcome: EventOutcome = EventOutcome {
+            ineligible_next_card: vec![],
+            free_commands: vec![(Dux, CommandType::Train)],
+            free_feats: vec![],
+        };
+
+        // Declined, nothing happens
+        let before: Board = board.clone();
+        sop.apply_outcome(
+            outcome.clone(),
+            &mut board,
+            &mut Dice::new(&mut ChaCha8Rng::seed_from_u64(0)),
+            &mut Scripted::new(vec![1]),
+        )
+        .unwrap();
+        assert_eq!(board.track(Track::DuxResources), resources);
+        assert_eq!(
+            board.available_units(Dux, UnitClass::Cavalry),
+            before.available_units(Dux, UnitClass::Cavalry)
+        );
+
+        // Taken, the Train costs the Dux nothing
+        sop.apply_outcome(
+            outcome,
+            &mut board,
+            &mut Dice::new(&mut ChaCha8Rng::seed_from_u64(0)),
+            &mut ScriptedThenBot(Scripted::new(vec![0]), Bot::new(0)),
+        )
+        .unwrap();
+        assert_eq!(board.track(Track::DuxResources), resources);
+        assert!(
+            board.available_units(Dux, UnitClass::Cavalry)
+                < before.available_units(Dux, UnitClass::Cavalry)
+                || board.total_prosperity() > before.total_prosperity()
+        );
+    }
+
+    // The scripted answers first, then a bot for whatever the command asks
+    struct ScriptedThenBot(Scripted, Bot);
+
+    impl DecisionProvider for ScriptedThenBot {
+        fn decide(
+            &mut self,
+            player: Player,
+            decision: &Decision,
+            view: Option<&DeckView>,
+        ) -> Result<usize, Interrupt> {
+            if self.0.remaining() > 0 {
+                return self.0.decide(player, decision, view);
+            }
+            self.1.decide(player, decision, view)
+        }
+    }
+
+    // Runs the card up to the next faction's choice and makes it
+    fn take_turn(mut sop: SequenceOfPlay, action: Action, board: &mut Board) -> SequenceOfPlay {
+        while sop.state != SequenceOfPlayState::ChoosingAction {
+            sop = match sop.state {
+                SequenceOfPlayState::CheckEndRound => sop.check_end_round().unwrap(),
+                SequenceOfPlayState::CheckPlayerStatus => sop.check_player_status().unwrap(),
+                SequenceOfPlayState::OfferPivotal => {
+                    sop.state = SequenceOfPlayState::CheckEndRound;
+                    sop
+                }
+                state => panic!(
+                    "Card ended before {:?} could be taken, in {:?}",
+                    action, state
+                ),
+            };
         }
+        let player: Player = sop.current_faction().unwrap();
+        let answers: Vec<usize> = vec![
+            sop.offered_actions(player, board)
+                .iter()
+                .position(|a| *a == action)
+                .unwrap(),
+        ];
+        let mut decisions: ScriptedThenBot = ScriptedThenBot(Scripted::new(answers), Bot::new(0));
+        sop = sop.get_action(board, &mut decisions).unwrap();
+        return sop
+            .acting(
+                board,
+                &mut Dice::new(&mut ChaCha8Rng::seed_from_u64(0)),
+                &mut decisions,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_passing_factions_are_replaced_in_order() {
+        let mut board: Board = setup_etty_tyrants();
+        let mut sop: SequenceOfPlay = new_sop();
+        let order: Vec<Player> = sop.current_event.eligibility.clone();
+
+        sop = take_turn(sop, Action::Pass, &mut board);
+        sop = take_turn(sop, Action::CommandOnly, &mut board);
+        assert_eq!(sop.available_actions.state, AfterCommandOnly);
+        sop = take_turn(sop, Action::Pass, &mut board);
+        sop = take_turn(sop, Action::LimitedCommand, &mut board);
+        sop = sop.check_end_round().unwrap();
+        assert_eq!(sop.state, SequenceOfPlayState::ResetEligibility);
+
+        sop = sop.reset_eligibility().unwrap();
+        assert!(matches!(sop.player_eligibilities[&order[0]], Eligible));
+        assert!(matches!(
+            sop.player_eligibilities[&order[1]],
+            PlayerState::Ineligible
+        ));
+        assert!(matches!(sop.player_eligibilities[&order[2]], Eligible));
+        assert!(matches!(
+            sop.player_eligibilities[&order[3]],
+            PlayerState::Ineligible
+        ));
+    }
+
+    #[test]
+    fn test_card_ends_when_everyone_passes() {
+        let mut board: Board = setup_etty_tyrants();
+        let mut sop: SequenceOfPlay = new_sop();
+        for _ in 0..4 {
+            sop = take_turn(sop, Action::Pass, &mut board);
+        }
+        assert_eq!(sop.available_actions.state, FirstEligible);
+        sop = sop.check_end_round().unwrap();
+        assert_eq!(sop.state, SequenceOfPlayState::ResetEligibility);
+        sop = sop.reset_eligibility().unwrap();
+        assert!(
+            sop.player_eligibilities
+                .values()
+                .all(|p| matches!(p, Eligible))
+        );
+    }
+
+    #[test]
+    fn test_untranscribed_events_are_not_offered() {
+        let mut board: Board = setup_etty_tyrants();
+        let mut sop: SequenceOfPlay = new_sop();
+        sop.state = SequenceOfPlayState::ChoosingAction;
+        assert!(sop.available_actions.a.contains(&Action::Event));
+        assert!(
+            !sop.offered_actions(sop.current_faction().unwrap(), &board)
+                .contains(&Action::Event)
+        );
+
+        // Picked anyway, it is refused and can be chosen again
+        sop.state = SequenceOfPlayState::Acting;
+        sop.selected_action = Some(Action::Event);
+        sop.selected_side = Some(Side::Unshaded);
+        let e = sop
+            .acting(
+                &mut board,
+                &mut Dice::new(&mut ChaCha8Rng::seed_from_u64(0)),
+                &mut Scripted::new(vec![]),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            e,
+            SopError::Event(EventError::NotImplemented { .. })
+        ));
+        assert!(e.is_recoverable());
+    }
+
+    #[test]
+    fn test_second_eligible_ends_the_card() {
+        let mut board: Board = setup_etty_tyrants();
+        let mut sop: SequenceOfPlay = new_sop();
+        sop.current_event.eligibility = vec![Civitates, Dux, Saxons, Scotti];
+        sop = take_turn(sop, Action::CommandOnly, &mut board);
+        sop = take_turn(sop, Action::LimitedCommand, &mut board);
+        sop = sop.check_end_round().unwrap();
+        assert_eq!(sop.state, SequenceOfPlayState::ResetEligibility);
+        assert_eq!(sop.current_player, 2);
+    }
+
+    #[test]
+    fn test_only_what_can_be_carried_out_is_offered() {
+        let board: Board = setup_etty_tyrants();
+        let sop: SequenceOfPlay = new_sop();
+        assert!(sop.available_actions.a.contains(&Action::CommandFeat));
+        // No Feat is written yet, and nothing at all for the Saxons
+        assert_eq!(
+            sop.offered_actions(Civitates, &board),
+            vec![Action::Pass, Action::CommandOnly]
+        );
+        assert_eq!(sop.offered_actions(Saxons, &board), vec![Action::Pass]);
     }
 }
@@ -1,108 +1,353 @@
+use dialoguer::Select;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
 
-use super::concepts::Player::{Civitates, Dux, Saxons, Scotti};
+use super::board::{self, Board};
 use super::events::{Event, EventType};
 
-pub fn build_deck() -> VecDeque<Event> {
-    let calleva_atrebatum: Event = Event {
-        name: String::from("Calleva Atrebatum"),
-        eligibility: vec![Saxons, Scotti, Dux, Civitates],
-        unshaded: None,
-        shaded: None,
-        historical_notes: String::from(""),
-        event_type: EventType::Standard,
-    };
-    let ard_ri: Event = Event {
-        name: String::from("Ard Ri"),
-        eligibility: vec![Scotti, Dux, Saxons, Civitates],
-        unshaded: None,
-        shaded: None,
-        historical_notes: String::from(""),
-        event_type: EventType::Standard,
-    };
-    let anderida: Event = Event {
-        name: String::from("Anderida"),
-        eligibility: vec![Saxons, Dux, Scotti, Civitates],
-        unshaded: None,
-        shaded: None,
-        historical_notes: String::from(""),
-        event_type: EventType::Standard,
-    };
-    let classis_britannica: Event = Event {
-        name: String::from("Classis Britannica"),
-        eligibility: vec![Dux, Saxons, Civitates, Scotti],
-        unshaded: None,
-        shaded: None,
-        historical_notes: String::from(""),
-        event_type: EventType::Standard,
-    };
-    let recruits: Event = Event {
-        name: String::from("Recruits"),
-        eligibility: vec![Dux, Scotti, Saxons, Civitates],
-        unshaded: None,
-        shaded: None,
-        historical_notes: String::from(""),
-        event_type: EventType::Standard,
-    };
-    let deira: Event = Event {
-        name: String::from("Deira"),
-        eligibility: vec![Civitates, Saxons, Dux, Scotti],
-        unshaded: None,
-        shaded: None,
-        historical_notes: String::from(""),
-        event_type: EventType::Standard,
-    };
-    let with_the_cross_on_his_shoulders: Event = Event {
-        name: String::from("With The Cross On His Shoulders"),
-        eligibility: vec![Civitates, Scotti, Dux, Saxons],
-        unshaded: None,
-        shaded: None,
-        historical_notes: String::from(""),
-        event_type: EventType::Standard,
-    };
-    let ambrosius_aurelianus: Event = Event {
-        name: String::from("Ambrosius Aurelianus"),
-        eligibility: vec![Civitates, Dux, Scotti, Saxons],
-        unshaded: None,
-        shaded: None,
-        historical_notes: String::from(""),
-        event_type: EventType::Standard,
-    };
-    let celyddon_coed: Event = Event {
-        name: String::from("Celyddon Coed"),
-        eligibility: vec![Scotti, Dux, Civitates, Saxons],
-        unshaded: None,
-        shaded: None,
-        historical_notes: String::from(""),
-        event_type: EventType::Standard,
-    };
-    let fickle_weather: Event = Event {
-        name: String::from("Fickle Weather"),
-        eligibility: vec![Scotti, Dux, Civitates, Saxons],
-        unshaded: None,
-        shaded: None,
-        historical_notes: String::from(""),
-        event_type: EventType::Standard,
-    };
-    let magnus_maximus: Event = Event {
-        name: String::from("Magnus Maximus"),
-        eligibility: vec![],
-        unshaded: None,
-        shaded: None,
-        historical_notes: String::from(""),
-        event_type: EventType::Epoch,
-    };
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Scenario {
+    BarbarianConspiracy,
+    EttyTyrants,
+    TheHarderTheyFall,
+    DeExcidioBritanniae,
+}
+
+impl Scenario {
+    pub const ALL: [Scenario; 4] = [
+        Scenario::BarbarianConspiracy,
+        Scenario::EttyTyrants,
+        Scenario::TheHarderTheyFall,
+        Scenario::DeExcidioBritanniae,
+    ];
+
+    // How the scenario is named on the command line and in file names
+    pub fn key(&self) -> &'static str {
+        match self {
+            Scenario::BarbarianConspiracy => "barbarian-conspiracy",
+            Scenario::EttyTyrants => "petty-tyrants",
+            Scenario::TheHarderTheyFall => "the-harder-they-fall",
+            Scenario::DeExcidioBritanniae => "de-excidio-britanniae",
+        }
+    }
+
+    pub fn board(&self) -> Board {
+        match self {
+            Scenario::BarbarianConspiracy => board::setup_barbarian_conspiracy(),
+            Scenario::EttyTyrants => board::setup_etty_tyrants(),
+            Scenario::TheHarderTheyFall => board::setup_the_harder_they_fall(),
+            Scenario::DeExcidioBritanniae => board::setup_de_excidio_britanniae(),
+        }
+    }
+
+    // The catalogue holds a single Epoch card so far, Magnus Maximus, so every
+    // scenario stacks one pile of it. Each scenario's later Epochs go at the
+    // end of its list as their cards are added to data/events.toml. The pile
+    // sizes are not from the scenario sheets either; until their deck
+    // preparation is transcribed, the scenarios all deal the same deck.
+    pub fn deck_setup(&self) -> DeckSetup {
+        match self {
+            Scenario::BarbarianConspiracy => DeckSetup {
+                epochs: &[11],
+                cards_per_pile: 10,
+                epoch_depth: 4,
+            },
+            Scenario::EttyTyrants => DeckSetup {
+                epochs: &[11],
+                cards_per_pile: 10,
+                epoch_depth: 4,
+            },
+            Scenario::TheHarderTheyFall => DeckSetup {
+                epochs: &[11],
+                cards_per_pile: 10,
+                epoch_depth: 4,
+            },
+            Scenario::DeExcidioBritanniae => DeckSetup {
+                epochs: &[11],
+                cards_per_pile: 10,
+                epoch_depth: 4,
+            },
+        }
+    }
+
+    pub fn deck(
+        &self,
+        cards: Vec<Event>,
+        rng: &mut ChaCha8Rng,
+    ) -> Result<VecDeque<Event>, DeckError> {
+        build_deck(cards, &self.deck_setup(), rng)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeckError {
+    // The scenario stacks a pile on an Epoch card the catalogue lacks
+    MissingEpoch(u8),
+    // Not enough standard events to deal every pile in full
+    TooFewCards { needed: usize, found: usize },
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeckError::MissingEpoch(number) => {
+                write!(f, "The catalogue has no Epoch card {}", number)
+            }
+            DeckError::TooFewCards { needed, found } => write!(
+                f,
+                "The deck needs {} standard events but the catalogue has {}",
+                needed, found
+            ),
+        }
+    }
+}
+
+// How a scenario deals its deck
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeckSetup {
+    // Numbers of the Epoch cards, one per pile, in the order the piles are
+    // stacked
+    pub epochs: &'static [u8],
+    // Standard events in each pile before its Epoch card is added
+    pub cards_per_pile: usize,
+    // Each Epoch card is shuffled in among this many cards at the bottom of
+    // its pile so that an Epoch never comes up at the very start of a pile
+    pub epoch_depth: usize,
+}
+
+impl fmt::Display for Scenario {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scenario::BarbarianConspiracy => write!(f, "The Barbarian Conspiracy"),
+            Scenario::EttyTyrants => write!(f, "Petty Tyrants"),
+            Scenario::TheHarderTheyFall => write!(f, "The Harder They Fall"),
+            Scenario::DeExcidioBritanniae => write!(f, "De Excidio Britanniae"),
+        }
+    }
+}
+
+// Scenarios are named on the command line in kebab case, e.g.
+// barbarian-conspiracy
+impl FromStr for Scenario {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let key: String = s.to_lowercase();
+        Scenario::ALL
+            .iter()
+            .copied()
+            .find(|scenario| scenario.key() == key)
+            .ok_or_else(|| {
+                format!(
+                    "unknown scenario {}, expected one of barbarian-conspiracy, petty-tyrants, the-harder-they-fall, de-excidio-britanniae",
+                    s
+                )
+            })
+    }
+}
+
+pub fn choose_scenario() -> Result<Scenario, dialoguer::Error> {
+    let selection: usize = Select::new()
+        .with_prompt("Select a scenario")
+        .items(&Scenario::ALL)
+        .default(0)
+        .interact()?;
+    return Ok(Scenario::ALL[selection]);
+}
+
+// Epoch cards the scenario does not list are out of the game
+pub fn build_deck(
+    cards: Vec<Event>,
+    setup: &DeckSetup,
+    rng: &mut ChaCha8Rng,
+) -> Result<VecDeque<Event>, DeckError> {
+    let (mut epochs, standard): (Vec<Event>, Vec<Event>) = cards
+        .into_iter()
+        .filter(|e| e.event_type != EventType::Pivotal)
+        .partition(|e| e.event_type == EventType::Epoch);
+    epochs.retain(|e| setup.epochs.contains(&e.number));
+    if let Some(missing) = setup
+        .epochs
+        .iter()
+        .find(|n| !epochs.iter().any(|e| e.number == **n))
+    {
+        return Err(DeckError::MissingEpoch(*missing));
+    }
+    epochs.sort_by_key(|e| setup.epochs.iter().position(|n| *n == e.number));
+    return stack_deck(standard, epochs, setup, rng);
+}
+
+// Shuffle the standard events, deal one pile per Epoch card, seed each
+// Epoch card into the bottom of its pile and stack the piles with the first
+// Epoch's pile on top. Standard events left over once every pile is full
+// are out of the game; too few to fill them all is an error.
+pub fn stack_deck(
+    mut standard: Vec<Event>,
+    epochs: Vec<Event>,
+    setup: &DeckSetup,
+    rng: &mut ChaCha8Rng,
+) -> Result<VecDeque<Event>, DeckError> {
+    let needed: usize = epochs.len() * setup.cards_per_pile;
+    if standard.len() < needed {
+        return Err(DeckError::TooFewCards {
+            needed,
+            found: standard.len(),
+        });
+    }
+    standard.shuffle(rng);
+    let mut remaining = standard.into_iter();
     let mut deck: VecDeque<Event> = VecDeque::new();
-    deck.push_back(calleva_atrebatum);
-    deck.push_back(ard_ri);
-    deck.push_back(anderida);
-    deck.push_back(classis_britannica);
-    deck.push_back(recruits);
-    deck.push_back(deira);
-    deck.push_back(with_the_cross_on_his_shoulders);
-    deck.push_back(ambrosius_aurelianus);
-    deck.push_back(celyddon_coed);
-    deck.push_back(fickle_weather);
-    deck.push_back(magnus_maximus);
-    return deck;
+    for epoch in epochs {
+        let mut pile: Vec<Event> = remaining.by_ref().take(setup.cards_per_pile).collect();
+        let split: usize = pile.len().saturating_sub(setup.epoch_depth);
+        let mut bottom: Vec<Event> = pile.split_off(split);
+        bottom.push(epoch);
+        bottom.shuffle(rng);
+        pile.append(&mut bottom);
+        deck.extend(pile);
+    }
+    return Ok(deck);
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::super::concepts::Player::{Civitates, Dux, Saxons, Scotti};
+    use super::super::events::load_catalogue;
+    use super::*;
+
+    fn card(number: u8, name: String, event_type: EventType) -> Event {
+        Event {
+            number,
+            name,
+            eligibility: vec![Civitates, Dux, Saxons, Scotti],
+            unshaded: String::from(""),
+            shaded: String::from(""),
+            historical_notes: String::from(""),
+            event_type,
+            faction: None,
+        }
+    }
+
+    fn names(deck: &VecDeque<Event>) -> Vec<String> {
+        deck.iter().map(|e| e.name.clone()).collect()
+    }
+
+    #[test]
+    fn test_same_seed_same_deck() {
+        let cards: Vec<Event> = load_catalogue().unwrap();
+        let scenario: Scenario = Scenario::BarbarianConspiracy;
+        let first = scenario
+            .deck(cards.clone(), &mut ChaCha8Rng::seed_from_u64(367))
+            .unwrap();
+        let second = scenario
+            .deck(cards.clone(), &mut ChaCha8Rng::seed_from_u64(367))
+            .unwrap();
+        assert_eq!(names(&first), names(&second));
+        let pivotals = cards
+            .iter()
+            .filter(|e| e.event_type == EventType::Pivotal)
+            .count();
+        assert_eq!(first.len(), cards.len() - pivotals);
+
+        let shuffled = (0..20)
+            .map(|seed| {
+                names(
+                    &scenario
+                        .deck(cards.clone(), &mut ChaCha8Rng::seed_from_u64(seed))
+                        .unwrap(),
+                )
+            })
+            .any(|deck| deck != names(&first));
+        assert!(shuffled);
+    }
+
+    #[test]
+    fn test_every_scenario_deals_its_epochs() {
+        let cards: Vec<Event> = load_catalogue().unwrap();
+        for scenario in Scenario::ALL {
+            let setup: DeckSetup = scenario.deck_setup();
+            let deck = scenario
+                .deck(cards.clone(), &mut ChaCha8Rng::seed_from_u64(0))
+                .unwrap();
+            let epochs: Vec<u8> = deck
+                .iter()
+                .filter(|e| e.event_type == EventType::Epoch)
+                .map(|e| e.number)
+                .collect();
+            assert_eq!(epochs, setup.epochs);
+        }
+    }
+
+    #[test]
+    fn test_epochs_land_in_their_piles() {
+        let setup: DeckSetup = DeckSetup {
+            epochs: &[102, 100, 101],
+            cards_per_pile: 8,
+            epoch_depth: 4,
+        };
+        let mut cards: Vec<Event> = (0..30)
+            .map(|i| card(i, format!("Standard {}", i), EventType::Standard))
+            .collect();
+        // An Epoch the scenario does not list stays out
+        cards.extend((0..4).map(|i| card(100 + i, format!("Epoch {}", i), EventType::Epoch)));
+
+        for seed in 0..200 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let mut deck = build_deck(cards.clone(), &setup, &mut rng).unwrap();
+            assert_eq!(deck.len(), 3 * (setup.cards_per_pile + 1));
+
+            for (pile, cards) in deck
+                .make_contiguous()
+                .chunks(setup.cards_per_pile + 1)
+                .enumerate()
+            {
+                let pile_epochs: Vec<&Event> = cards
+                    .iter()
+                    .filter(|e| e.event_type == EventType::Epoch)
+                    .collect();
+                assert_eq!(pile_epochs.len(), 1);
+                assert_eq!(pile_epochs[0].number, setup.epochs[pile]);
+
+                let position = cards
+                    .iter()
+                    .position(|e| e.event_type == EventType::Epoch)
+                    .unwrap();
+                assert!(position >= setup.cards_per_pile - setup.epoch_depth);
+            }
+        }
+    }
+
+    #[test]
+    fn test_piles_are_never_dealt_short() {
+        let setup: DeckSetup = DeckSetup {
+            epochs: &[100, 101],
+            cards_per_pile: 8,
+            epoch_depth: 4,
+        };
+        let mut cards: Vec<Event> = (0..15)
+            .map(|i| card(i, format!("Standard {}", i), EventType::Standard))
+            .collect();
+        cards.push(card(100, String::from("Epoch 0"), EventType::Epoch));
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        assert_eq!(
+            build_deck(cards.clone(), &setup, &mut rng).unwrap_err(),
+            DeckError::MissingEpoch(101)
+        );
+        cards.push(card(101, String::from("Epoch 1"), EventType::Epoch));
+        assert_eq!(
+            build_deck(cards.clone(), &setup, &mut rng).unwrap_err(),
+            DeckError::TooFewCards {
+                needed: 16,
+                found: 15
+            }
+        );
+        cards.push(card(15, String::from("Standard 15"), EventType::Standard));
+        assert_eq!(build_deck(cards, &setup, &mut rng).unwrap().len(), 18);
+    }
 }
@@ -1,23 +1,6 @@
 
 
 
-fn civitates_march() {}
-
-fn civitates_trade() {}
-
-fn civitates_battle() {}
-
-fn dux_train() {
-    /*
-       1. Place troops where Fort (cost 3)
-           Place cavalry
-           May place militia if Civitates stronghold
-       2. Add prosperity where Fort or friendly control (cost 2)
-           Add prosperity
-
-    */
-}
-
 fn dux_march() {
     /*
         My Sisyphean task
@@ -119,10 +102,6 @@ fn scotti_entreat() {}
 
 // Helper functions
 
-fn move_units() {}
-
-fn battle() {}
-
 fn get_spaces() {}
 
 struct GameMap {}